@@ -0,0 +1,162 @@
+use crate::time::{Duration, Instant};
+
+const ACTIVATION_MOD: u32 = 100;
+// External events kept in the history
+pub const HISTORY_LENGTH: usize = 16;
+
+// External event recorded by the external event server
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub struct LogEntry {
+    // Sequence number of the event, the first one is 1
+    pub sequence: u32,
+    pub time: Instant,
+    // Time since the previous event
+    pub interarrival: Option<Duration>,
+}
+
+pub struct ActivationLog {
+    activation_counter: u32,
+    last_activation_time: Option<Instant>,
+    // Ring of the last HISTORY_LENGTH entries, indexed by sequence number
+    history: [Option<LogEntry>; HISTORY_LENGTH],
+    last_sequence: u32,
+    min_interarrival: Option<Duration>,
+    max_interarrival: Option<Duration>,
+}
+
+impl ActivationLog {
+    pub fn new() -> Self {
+        ActivationLog {
+            activation_counter: 0,
+            last_activation_time: None,
+            history: [None; HISTORY_LENGTH],
+            last_sequence: 0,
+            min_interarrival: None,
+            max_interarrival: None,
+        }
+    }
+
+    pub fn write(&mut self, now: Instant) {
+        let interarrival = self.last_activation_time.map(|last| now - last);
+        if let Some(interarrival) = interarrival {
+            self.min_interarrival =
+                Some(self.min_interarrival.map_or(interarrival, |min| min.min(interarrival)));
+            self.max_interarrival =
+                Some(self.max_interarrival.map_or(interarrival, |max| max.max(interarrival)));
+        }
+
+        self.last_sequence += 1;
+        self.history[self.last_sequence as usize % HISTORY_LENGTH] = Some(LogEntry {
+            sequence: self.last_sequence,
+            time: now,
+            interarrival,
+        });
+        self.activation_counter = (self.activation_counter + 1) % ACTIVATION_MOD;
+        self.last_activation_time = Some(now);
+    }
+
+    pub fn read(&self) -> (u32, Option<Instant>) {
+        (self.activation_counter, self.last_activation_time)
+    }
+
+    // Sequence number of the last entry, 0 if the log is empty
+    pub fn last_sequence(&self) -> u32 {
+        self.last_sequence
+    }
+
+    // Oldest entry still in the history
+    fn first_available(&self) -> u32 {
+        (self.last_sequence + 1).saturating_sub(HISTORY_LENGTH as u32).max(1)
+    }
+
+    // Entries written after `sequence` that are still in the history, oldest first
+    pub fn entries_since(&self, sequence: u32) -> impl Iterator<Item = LogEntry> + '_ {
+        (self.first_available().max(sequence + 1)..=self.last_sequence)
+            .filter_map(|sequence| self.history[sequence as usize % HISTORY_LENGTH])
+    }
+
+    // Entries written after `sequence` and overwritten before being read
+    pub fn dropped_since(&self, sequence: u32) -> u32 {
+        self.first_available().saturating_sub(sequence + 1)
+    }
+
+    // Observed interarrival range of the external events
+    pub fn interarrival(&self) -> Option<(Duration, Duration)> {
+        self.min_interarrival.zip(self.max_interarrival)
+    }
+}
+
+impl Default for ActivationLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockMono;
+
+    #[test]
+    fn empty_log_has_no_last_activation() {
+        let log = ActivationLog::new();
+        assert_eq!(log.read(), (0, None));
+        assert_eq!(log.entries_since(0).count(), 0);
+        assert_eq!(log.interarrival(), None);
+    }
+
+    #[test]
+    fn write_records_last_activation_time() {
+        let mut mono = MockMono::new();
+        let mut log = ActivationLog::new();
+
+        log.write(mono.advance(Duration::millis(10)));
+        log.write(mono.advance(Duration::millis(25)));
+
+        assert_eq!(log.read(), (2, Some(Instant::from_ticks(0) + Duration::millis(35))));
+    }
+
+    #[test]
+    fn counter_wraps_at_activation_mod() {
+        let mono = MockMono::new();
+        let mut log = ActivationLog::new();
+
+        for _ in 0..ACTIVATION_MOD + 3 {
+            log.write(mono.now());
+        }
+
+        assert_eq!(log.read().0, 3);
+    }
+
+    #[test]
+    fn entries_since_a_sequence_number_carry_their_interarrival() {
+        let mut mono = MockMono::new();
+        let mut log = ActivationLog::new();
+        for millis in [10, 30, 20] {
+            log.write(mono.advance(Duration::millis(millis)));
+        }
+
+        let entries: Vec<_> = log.entries_since(1).collect();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].sequence, 2);
+        assert_eq!(entries[0].interarrival, Some(Duration::millis(30)));
+        assert_eq!(entries[1].time, Instant::from_ticks(0) + Duration::millis(60));
+        assert_eq!(log.entries_since(log.last_sequence()).count(), 0);
+        assert_eq!(log.interarrival(), Some((Duration::millis(20), Duration::millis(30))));
+    }
+
+    #[test]
+    fn overwritten_entries_are_reported_as_dropped() {
+        let mut mono = MockMono::new();
+        let mut log = ActivationLog::new();
+        for _ in 0..HISTORY_LENGTH + 4 {
+            log.write(mono.advance(Duration::millis(10)));
+        }
+
+        // A reader that has read up to entry 2 lost entries 3 and 4
+        assert_eq!(log.dropped_since(2), 2);
+        let sequences: Vec<_> = log.entries_since(2).map(|entry| entry.sequence).collect();
+        assert_eq!(sequences, (5..=HISTORY_LENGTH as u32 + 4).collect::<Vec<_>>());
+        assert_eq!(log.dropped_since(10), 0);
+    }
+}
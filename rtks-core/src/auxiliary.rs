@@ -0,0 +1,88 @@
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+// Part of the system configuration
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub struct Moduli {
+    pub due_activation: usize,
+    pub check_due: usize,
+    pub check_due_factor: usize,
+}
+
+impl Moduli {
+    pub const DEFAULT: Moduli = Moduli {
+        due_activation: 5,
+        // A multiple of the factor, so the wrap keeps the runs evenly spaced
+        check_due: 999,
+        check_due_factor: 3,
+    };
+}
+
+pub fn due_activation(param: usize, moduli: &Moduli) -> bool {
+    static REQUEST_COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let counter = REQUEST_COUNTER.fetch_add(1, Ordering::Relaxed) + 1;
+
+    is_due_activation(counter, param, moduli)
+}
+
+pub fn check_due(moduli: &Moduli) -> bool {
+    static RUN_COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let counter = RUN_COUNTER.fetch_add(1, Ordering::Relaxed) + 1;
+
+    is_check_due(counter, moduli)
+}
+
+// Fewest runs between two consecutive checks due, at the wrap of the run
+// counter when the mod is not a multiple of the factor
+pub fn min_check_due_gap(moduli: &Moduli) -> usize {
+    match moduli.check_due % moduli.check_due_factor {
+        0 => moduli.check_due_factor,
+        remainder => remainder,
+    }
+}
+
+fn is_due_activation(counter: usize, param: usize, moduli: &Moduli) -> bool {
+    counter % moduli.due_activation == param % moduli.due_activation
+}
+
+fn is_check_due(counter: usize, moduli: &Moduli) -> bool {
+    (counter % moduli.check_due).is_multiple_of(moduli.check_due_factor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn due_activation_once_every_mod_requests() {
+        let due: Vec<usize> =
+            (1..=15).filter(|&c| is_due_activation(c, 2, &Moduli::DEFAULT)).collect();
+        assert_eq!(due, [2, 7, 12]);
+    }
+
+    #[test]
+    fn check_due_every_factor_runs_within_mod() {
+        let moduli = Moduli::DEFAULT;
+        let due: Vec<usize> = (1..=10).filter(|&c| is_check_due(c, &moduli)).collect();
+        assert_eq!(due, [3, 6, 9]);
+        // The run counter restarts the pattern at every multiple of the mod
+        assert!(is_check_due(moduli.check_due, &moduli));
+        assert!(!is_check_due(moduli.check_due + 1, &moduli));
+        assert_eq!(min_check_due_gap(&moduli), 3);
+    }
+
+    #[test]
+    fn check_due_gap_shrinks_at_the_wrap() {
+        let moduli = Moduli { check_due: 1000, ..Moduli::DEFAULT };
+        assert!(is_check_due(999, &moduli) && is_check_due(1000, &moduli));
+        assert_eq!(min_check_due_gap(&moduli), 1);
+        // A mod below the factor only has the check at the wrap
+        assert_eq!(min_check_due_gap(&Moduli { check_due: 2, ..Moduli::DEFAULT }), 2);
+    }
+
+    #[test]
+    fn due_activation_counter_is_shared_between_calls() {
+        let moduli = Moduli::DEFAULT;
+        let due = (0..moduli.due_activation).filter(|_| due_activation(0, &moduli)).count();
+        assert_eq!(due, 1);
+    }
+}
@@ -0,0 +1,252 @@
+// Execution-time budgets of the tasks, enforced with the cycle counter.
+// A job consumes the cycles elapsed since its start minus the cycles spent by
+// the jobs that preempted it, which every budgeted job charges on completion
+// to the priority levels below its own. The deadline timer service and the
+// external interrupt are short and are not charged.
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use crate::{
+    task_set::TaskParameters,
+    timing::{cycles_to_micros, CYCLES_PER_MICROSECOND},
+};
+
+// Priority levels of the Cortex-M4 NVIC
+const PRIORITY_LEVELS: usize = 16;
+
+// Cycles consumed above each priority level
+pub struct Interference {
+    levels: [AtomicU32; PRIORITY_LEVELS],
+}
+
+impl Interference {
+    pub const fn new() -> Self {
+        Interference {
+            levels: [const { AtomicU32::new(0) }; PRIORITY_LEVELS],
+        }
+    }
+
+    fn above(&self, priority: u8) -> u32 {
+        self.levels[priority as usize].load(Ordering::Relaxed)
+    }
+
+    fn charge(&self, priority: u8, cycles: u32) {
+        for level in self.levels[..priority as usize].iter() {
+            level.fetch_add(cycles, Ordering::Relaxed);
+        }
+    }
+}
+
+impl Default for Interference {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Shared by all the tasks of the firmware
+pub static INTERFERENCE: Interference = Interference::new();
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub struct Overrun {
+    pub task: &'static str,
+    pub job: u32,
+    // Microseconds
+    pub budget: u32,
+    pub consumed: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum OverrunAction {
+    Continue,
+    // Give up the remaining work of the job at its next abort point
+    AbortJob,
+}
+
+// Invoked once per job, when the job exceeds its budget
+pub type OverrunHandler = fn(&Overrun) -> OverrunAction;
+
+pub fn log_overrun(overrun: &Overrun) -> OverrunAction {
+    defmt::error!(
+        "Budget overrun for job {} of task '{}': {} us consumed, budget {} us",
+        overrun.job,
+        overrun.task,
+        overrun.consumed,
+        overrun.budget
+    );
+    OverrunAction::Continue
+}
+
+pub fn abort_on_overrun(overrun: &Overrun) -> OverrunAction {
+    log_overrun(overrun);
+    OverrunAction::AbortJob
+}
+
+// Budget of a task, the WCET it is given in the task set
+pub struct ExecutionBudget {
+    task: &'static str,
+    priority: u8,
+    budget: u32,
+    // Cycles of tolerance for a clock coarser than the cycle counter
+    resolution: u32,
+    handler: OverrunHandler,
+    interference: &'static Interference,
+}
+
+impl ExecutionBudget {
+    pub fn new(
+        task: &TaskParameters,
+        handler: OverrunHandler,
+        interference: &'static Interference,
+    ) -> Self {
+        ExecutionBudget {
+            task: task.name,
+            priority: task.priority,
+            budget: task.wcet * CYCLES_PER_MICROSECOND,
+            resolution: 0,
+            handler,
+            interference,
+        }
+    }
+
+    // Cycle counts measured with a clock of `resolution` cycles: consumption
+    // is only an overrun once it exceeds the budget by more than that
+    pub fn with_resolution(mut self, resolution: u32) -> Self {
+        self.resolution = resolution;
+        self
+    }
+
+    // `start` is the cycle counter value when the job got the CPU
+    pub fn start(&self, job: u32, start: u32) -> BudgetedJob<'_> {
+        self.start_scaled(job, start, 1)
+    }
+
+    // Job of a mode whose workloads are scaled by `factor`, with the budget
+    pub fn start_scaled(&self, job: u32, start: u32, factor: u32) -> BudgetedJob<'_> {
+        BudgetedJob {
+            budget: self,
+            limit: self.budget * factor,
+            job,
+            start,
+            interference: self.interference.above(self.priority),
+            action: None,
+        }
+    }
+}
+
+pub struct BudgetedJob<'a> {
+    budget: &'a ExecutionBudget,
+    // Cycles the job may consume
+    limit: u32,
+    job: u32,
+    start: u32,
+    interference: u32,
+    // Outcome of the handler, once the budget is exceeded
+    action: Option<OverrunAction>,
+}
+
+impl BudgetedJob<'_> {
+    // Cycles consumed by the job up to `now`
+    pub fn consumed(&self, now: u32) -> u32 {
+        let elapsed = now.wrapping_sub(self.start);
+        let preempted = self
+            .budget
+            .interference
+            .above(self.budget.priority)
+            .wrapping_sub(self.interference);
+        elapsed.wrapping_sub(preempted)
+    }
+
+    // Budget check, to be called at the abort points of the job
+    pub fn check(&mut self, now: u32) -> OverrunAction {
+        if self.action.is_none() {
+            let consumed = self.consumed(now);
+            if consumed > self.limit.saturating_add(self.budget.resolution) {
+                let overrun = Overrun {
+                    task: self.budget.task,
+                    job: self.job,
+                    budget: cycles_to_micros(self.limit),
+                    consumed: cycles_to_micros(consumed),
+                };
+                self.action = Some((self.budget.handler)(&overrun));
+            }
+        }
+        self.action.unwrap_or(OverrunAction::Continue)
+    }
+
+    // Job completed at `now`, returns the cycles it consumed
+    pub fn finish(mut self, now: u32) -> u32 {
+        self.check(now);
+        let consumed = self.consumed(now);
+        self.budget.interference.charge(self.budget.priority, consumed);
+        consumed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task_set::{ACTIVATION_LOG_READER, EXTERNAL_EVENT_SERVER};
+
+    static OVERRUNS: AtomicU32 = AtomicU32::new(0);
+
+    fn count_overrun(_: &Overrun) -> OverrunAction {
+        OVERRUNS.fetch_add(1, Ordering::Relaxed);
+        OverrunAction::AbortJob
+    }
+
+    const fn micros(micros: u32) -> u32 {
+        micros * CYCLES_PER_MICROSECOND
+    }
+
+    #[test]
+    fn preemption_is_not_charged_to_the_preempted_job() {
+        static INTERFERENCE: Interference = Interference::new();
+        let low = ExecutionBudget::new(&ACTIVATION_LOG_READER, log_overrun, &INTERFERENCE);
+        let high = ExecutionBudget::new(&EXTERNAL_EVENT_SERVER, log_overrun, &INTERFERENCE);
+
+        let low_job = low.start(1, 0);
+        // Preempted for 50 us
+        let high_job = high.start(1, micros(100));
+        assert_eq!(high_job.finish(micros(150)), micros(50));
+
+        assert_eq!(low_job.consumed(micros(400)), micros(350));
+        // Jobs at the preempting priority are not affected
+        assert_eq!(high.start(2, micros(400)).consumed(micros(410)), micros(10));
+    }
+
+    #[test]
+    fn handler_is_invoked_once_per_overrun() {
+        static INTERFERENCE: Interference = Interference::new();
+        let budget = ExecutionBudget::new(&ACTIVATION_LOG_READER, count_overrun, &INTERFERENCE);
+        let wcet = ACTIVATION_LOG_READER.wcet;
+
+        let mut job = budget.start(1, 0);
+        assert_eq!(job.check(micros(wcet)), OverrunAction::Continue);
+        assert_eq!(job.check(micros(wcet + 1)), OverrunAction::AbortJob);
+        assert_eq!(job.check(micros(wcet + 2)), OverrunAction::AbortJob);
+        job.finish(micros(wcet + 3));
+        assert_eq!(OVERRUNS.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn scaled_budget_covers_the_scaled_workload() {
+        static INTERFERENCE: Interference = Interference::new();
+        let budget = ExecutionBudget::new(&ACTIVATION_LOG_READER, abort_on_overrun, &INTERFERENCE);
+        let wcet = ACTIVATION_LOG_READER.wcet;
+
+        let mut job = budget.start_scaled(1, 0, 2);
+        assert_eq!(job.check(micros(2 * wcet)), OverrunAction::Continue);
+        assert_eq!(job.check(micros(2 * wcet + 1)), OverrunAction::AbortJob);
+    }
+
+    #[test]
+    fn coarse_clock_tolerates_one_tick_over_the_budget() {
+        static INTERFERENCE: Interference = Interference::new();
+        let budget = ExecutionBudget::new(&ACTIVATION_LOG_READER, abort_on_overrun, &INTERFERENCE)
+            .with_resolution(micros(1_000));
+        let wcet = ACTIVATION_LOG_READER.wcet;
+
+        let mut job = budget.start(1, 0);
+        assert_eq!(job.check(micros(wcet + 1_000)), OverrunAction::Continue);
+        assert_eq!(job.check(micros(wcet + 1_001)), OverrunAction::AbortJob);
+    }
+}
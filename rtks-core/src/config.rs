@@ -0,0 +1,293 @@
+// Run-time configuration of the task set: the parameters the tasks read at
+// each release. The firmware holds it as a shared resource, a new one is
+// validated before being installed and each task takes a copy at its release,
+// so a change takes effect at the next release boundary. It holds the nominal
+// parameters, the other modes scale them.
+use core::fmt;
+
+use crate::{
+    auxiliary::{self, Moduli},
+    mode::{ModeParameters, SystemMode},
+    task_set::{ACTIVATION_LOG_READER, ON_CALL_PRODUCER, REGULAR_PRODUCER, TaskParameters},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub struct SystemConfig {
+    // Period and relative deadline of the regular producer (milliseconds)
+    pub period: u32,
+    pub deadline: u32,
+    // Workloads (microseconds), the on call producer one is deposited by the
+    // regular producer with each request
+    pub regular_producer_workload: u32,
+    pub on_call_producer_workload: u32,
+    pub activation_log_reader_workload: u32,
+    // Run of the regular producer, modulo `moduli.due_activation`, that
+    // activates the on call producer
+    pub activation_condition: usize,
+    pub moduli: Moduli,
+}
+
+// Longest period of the regular producer (milliseconds), the deadline is within
+// it. Scaled by every mode it stays well under half the wrap of the monotonic,
+// beyond which a release would compare as already past
+pub const MAX_PERIOD: u32 = 600_000;
+
+// Single change to the configuration
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Setting {
+    Period(u32),
+    Deadline(u32),
+    Workload { task: &'static TaskParameters, micros: u32 },
+    ActivationCondition(usize),
+    DueActivationMod(usize),
+    CheckDueMod(usize),
+    CheckDueFactor(usize),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum ConfigError {
+    // The response-time analysis holds for the modelled period or a longer one
+    PeriodBelowModel { period: u32, model: u32 },
+    PeriodAboveMax { period: u32, max: u32 },
+    DeadlineOutOfRange { deadline: u32, period: u32 },
+    WorkloadOverWcet { task: &'static str, workload: u32, wcet: u32 },
+    NoWorkload(&'static str),
+    ZeroModulus,
+    // The regular producer would release a sporadic task more often than its
+    // modelled minimum interarrival
+    InterarrivalBelowModel { task: &'static str, interarrival: u32, model: u32 },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::PeriodBelowModel { period, model } => {
+                write!(f, "period {} ms below the modelled {} ms", period, model)
+            }
+            ConfigError::PeriodAboveMax { period, max } => {
+                write!(f, "period {} ms above the maximum of {} ms", period, max)
+            }
+            ConfigError::DeadlineOutOfRange { deadline, period } => {
+                write!(f, "deadline {} ms not within the period of {} ms", deadline, period)
+            }
+            ConfigError::WorkloadOverWcet { task, workload, wcet } => {
+                write!(f, "workload {} us of {} over its wcet of {} us", workload, task, wcet)
+            }
+            ConfigError::NoWorkload(task) => write!(f, "{} has no workload", task),
+            ConfigError::ZeroModulus => write!(f, "moduli must be positive"),
+            ConfigError::InterarrivalBelowModel { task, interarrival, model } => write!(
+                f,
+                "{} released every {} ms, below its modelled {} ms",
+                task, interarrival, model
+            ),
+        }
+    }
+}
+
+impl SystemConfig {
+    // Configuration the task set is analysed with
+    pub const DEFAULT: SystemConfig = SystemConfig {
+        period: REGULAR_PRODUCER.period(),
+        deadline: REGULAR_PRODUCER.deadline,
+        regular_producer_workload: 8_000,
+        on_call_producer_workload: 4_000,
+        activation_log_reader_workload: 12_000,
+        activation_condition: 2,
+        moduli: Moduli::DEFAULT,
+    };
+
+    // None for the tasks without a workload
+    pub fn workload(&self, task: &TaskParameters) -> Option<u32> {
+        match task.name {
+            name if name == REGULAR_PRODUCER.name => Some(self.regular_producer_workload),
+            name if name == ON_CALL_PRODUCER.name => Some(self.on_call_producer_workload),
+            name if name == ACTIVATION_LOG_READER.name => Some(self.activation_log_reader_workload),
+            _ => None,
+        }
+    }
+
+    fn workload_mut(&mut self, task: &TaskParameters) -> Result<&mut u32, ConfigError> {
+        match task.name {
+            name if name == REGULAR_PRODUCER.name => Ok(&mut self.regular_producer_workload),
+            name if name == ON_CALL_PRODUCER.name => Ok(&mut self.on_call_producer_workload),
+            name if name == ACTIVATION_LOG_READER.name => {
+                Ok(&mut self.activation_log_reader_workload)
+            }
+            _ => Err(ConfigError::NoWorkload(task.name)),
+        }
+    }
+
+    // Parameters in `mode`. The modes scale the workloads with the WCETs and the
+    // period with the deadline, so a valid configuration is valid in every mode
+    pub fn for_mode(&self, mode: SystemMode) -> SystemConfig {
+        let ModeParameters { load_factor, period_factor, .. } = mode.parameters();
+        SystemConfig {
+            period: self.period.saturating_mul(period_factor),
+            deadline: self.deadline.saturating_mul(period_factor),
+            regular_producer_workload: self.regular_producer_workload.saturating_mul(load_factor),
+            on_call_producer_workload: self.on_call_producer_workload.saturating_mul(load_factor),
+            activation_log_reader_workload: self
+                .activation_log_reader_workload
+                .saturating_mul(load_factor),
+            ..*self
+        }
+    }
+
+    // Checks the configuration against the analysed task set
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.period < REGULAR_PRODUCER.period() {
+            return Err(ConfigError::PeriodBelowModel {
+                period: self.period,
+                model: REGULAR_PRODUCER.period(),
+            });
+        }
+        if self.period > MAX_PERIOD {
+            return Err(ConfigError::PeriodAboveMax { period: self.period, max: MAX_PERIOD });
+        }
+        // Bounded by MAX_PERIOD too
+        if self.deadline == 0 || self.deadline > self.period {
+            return Err(ConfigError::DeadlineOutOfRange {
+                deadline: self.deadline,
+                period: self.period,
+            });
+        }
+        for task in [REGULAR_PRODUCER, ON_CALL_PRODUCER, ACTIVATION_LOG_READER] {
+            let workload = self.workload(&task).unwrap_or_default();
+            if workload > task.wcet {
+                return Err(ConfigError::WorkloadOverWcet {
+                    task: task.name,
+                    workload,
+                    wcet: task.wcet,
+                });
+            }
+        }
+        let Moduli { due_activation, check_due, check_due_factor } = self.moduli;
+        if due_activation == 0 || check_due == 0 || check_due_factor == 0 {
+            return Err(ConfigError::ZeroModulus);
+        }
+        let releases = [
+            (ON_CALL_PRODUCER, due_activation),
+            (ACTIVATION_LOG_READER, auxiliary::min_check_due_gap(&self.moduli)),
+        ];
+        for (task, runs) in releases {
+            let interarrival = self.period.saturating_mul(runs as u32);
+            if interarrival < task.period() {
+                return Err(ConfigError::InterarrivalBelowModel {
+                    task: task.name,
+                    interarrival,
+                    model: task.period(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    // Copy of the configuration with `setting` applied, if it is valid
+    pub fn with(&self, setting: Setting) -> Result<SystemConfig, ConfigError> {
+        let mut config = *self;
+        match setting {
+            Setting::Period(period) => config.period = period,
+            Setting::Deadline(deadline) => config.deadline = deadline,
+            Setting::Workload { task, micros } => *config.workload_mut(task)? = micros,
+            Setting::ActivationCondition(condition) => config.activation_condition = condition,
+            Setting::DueActivationMod(modulus) => config.moduli.due_activation = modulus,
+            Setting::CheckDueMod(modulus) => config.moduli.check_due = modulus,
+            Setting::CheckDueFactor(factor) => config.moduli.check_due_factor = factor,
+        }
+        config.validate().map(|()| config)
+    }
+}
+
+impl Default for SystemConfig {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task_set::EXTERNAL_EVENT_SERVER;
+
+    #[test]
+    fn default_configuration_is_valid() {
+        assert_eq!(SystemConfig::DEFAULT.validate(), Ok(()));
+        assert_eq!(SystemConfig::DEFAULT.workload(&EXTERNAL_EVENT_SERVER), None);
+    }
+
+    #[test]
+    fn settings_are_applied_to_a_copy() {
+        let config = SystemConfig::DEFAULT;
+        let updated = config
+            .with(Setting::Workload { task: &ON_CALL_PRODUCER, micros: 900 })
+            .and_then(|config| config.with(Setting::Period(2_000)))
+            .unwrap();
+        assert_eq!(updated.workload(&ON_CALL_PRODUCER), Some(900));
+        assert_eq!(updated.period, 2_000);
+        assert_eq!(config, SystemConfig::DEFAULT);
+    }
+
+    #[test]
+    fn high_load_scales_the_nominal_parameters() {
+        let config = SystemConfig::DEFAULT.for_mode(SystemMode::HighLoad);
+        let parameters = SystemMode::HighLoad.parameters();
+        assert_eq!(config.period, parameters.period(&REGULAR_PRODUCER));
+        assert_eq!(config.deadline, parameters.deadline(&REGULAR_PRODUCER));
+        assert_eq!(config.workload(&ON_CALL_PRODUCER), Some(8_000));
+        assert_eq!(SystemConfig::DEFAULT.for_mode(SystemMode::Degraded), SystemConfig::DEFAULT);
+    }
+
+    #[test]
+    fn settings_outside_the_model_are_rejected() {
+        let config = SystemConfig::DEFAULT;
+        assert!(matches!(
+            config.with(Setting::Period(500)),
+            Err(ConfigError::PeriodBelowModel { .. })
+        ));
+        assert!(matches!(
+            config.with(Setting::Deadline(config.period + 1)),
+            Err(ConfigError::DeadlineOutOfRange { .. })
+        ));
+        assert!(matches!(
+            config.with(Setting::Workload { task: &REGULAR_PRODUCER, micros: REGULAR_PRODUCER.wcet + 1 }),
+            Err(ConfigError::WorkloadOverWcet { .. })
+        ));
+        assert_eq!(
+            config.with(Setting::Workload { task: &EXTERNAL_EVENT_SERVER, micros: 1 }),
+            Err(ConfigError::NoWorkload(EXTERNAL_EVENT_SERVER.name))
+        );
+        assert_eq!(config.with(Setting::CheckDueMod(0)), Err(ConfigError::ZeroModulus));
+        // The on call producer would be released every 2 s, its minimum interarrival is 5 s
+        assert_eq!(
+            config.with(Setting::DueActivationMod(2)),
+            Err(ConfigError::InterarrivalBelowModel {
+                task: ON_CALL_PRODUCER.name,
+                interarrival: 2_000,
+                model: ON_CALL_PRODUCER.period(),
+            })
+        );
+        // Runs 999 and 1000 are both due, the reader would be released 1 s apart
+        assert_eq!(
+            config.with(Setting::CheckDueMod(1000)),
+            Err(ConfigError::InterarrivalBelowModel {
+                task: ACTIVATION_LOG_READER.name,
+                interarrival: 1_000,
+                model: ACTIVATION_LOG_READER.period(),
+            })
+        );
+    }
+
+    #[test]
+    fn longest_period_does_not_wrap_in_any_mode() {
+        let config = SystemConfig { period: MAX_PERIOD, ..SystemConfig::DEFAULT };
+        assert_eq!(config.validate(), Ok(()));
+        assert_eq!(
+            config.with(Setting::Period(3_000_000_000)),
+            Err(ConfigError::PeriodAboveMax { period: 3_000_000_000, max: MAX_PERIOD })
+        );
+        for mode in SystemMode::ALL {
+            // Half the wrap of the millisecond tick
+            assert!(config.for_mode(mode).period < u32::MAX / 2, "{:?}", mode);
+        }
+    }
+}
@@ -0,0 +1,638 @@
+// Command console on a serial line: the receive interrupt assembles the
+// characters into lines, the console task parses them into commands and writes
+// its replies back on the line. Replies are plain text, one item per line.
+use core::{
+    fmt::{self, Write},
+    ops::RangeInclusive,
+};
+
+use crate::{
+    activation_log::LogEntry,
+    config::{Setting, SystemConfig},
+    fault_injection::{Fault, FaultWindow, FLOOD_INTERARRIVALS, INFLATE_FACTORS, RELEASE_JITTERS},
+    mode::{ModeChangeRequest, SystemMode},
+    summary::RunSummary,
+    task_set::{Activation, TaskParameters, TASKS},
+    time::Duration,
+    timing::cycles_to_micros,
+};
+
+pub const LINE_LENGTH: usize = 64;
+
+const HELP: &str = "\
+help                            this list
+stats                           system counters
+tasks                           task set parameters and workloads
+deadlines                       deadline statistics of each task
+log                             external events in the activation log
+config                          system configuration
+mode                            current mode and pending mode change
+mode <mode>                     change to nominal, degraded or high-load mode
+set period <ms>                 change the period of rp
+set deadline <ms>               change the deadline of rp
+set workload <task> <us>        change the workload of rp, ocp or alr
+set activation-condition <n>    change the rp run that activates ocp
+set due-activation-mod <n>      change the rp runs between ocp activations
+set check-due-mod <n>           change the rp runs between alr pattern restarts
+set check-due-factor <n>        change the rp runs between alr activations
+trigger event                   signal an external event, replacing a pending interrupt,
+                                rejected by ees within its minimum interarrival
+faults                          injected fault windows
+fault <task> <n>[-<m>] <fault>  inject a fault on jobs n to m of a task, one of
+                                inflate <factor>, jitter <ms>, no-cancel, flood <ms>
+fault clear                     remove every fault window";
+
+// Command line, without its terminator
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Line {
+    bytes: [u8; LINE_LENGTH],
+    len: usize,
+}
+
+impl Line {
+    const EMPTY: Line = Line {
+        bytes: [0; LINE_LENGTH],
+        len: 0,
+    };
+
+    pub fn as_str(&self) -> &str {
+        // Only printable ASCII is stored
+        core::str::from_utf8(&self.bytes[..self.len]).unwrap_or_default()
+    }
+}
+
+// Characters received since the last line terminator
+pub struct LineBuffer {
+    line: Line,
+    overflow: bool,
+}
+
+impl LineBuffer {
+    pub const fn new() -> Self {
+        LineBuffer {
+            line: Line::EMPTY,
+            overflow: false,
+        }
+    }
+
+    // Returns the line terminated by `byte`, if any. Lines longer than
+    // LINE_LENGTH are dropped, control characters other than backspace ignored
+    pub fn push(&mut self, byte: u8) -> Option<Line> {
+        match byte {
+            b'\r' | b'\n' => {
+                let line = core::mem::replace(&mut self.line, Line::EMPTY);
+                let overflow = core::mem::replace(&mut self.overflow, false);
+                (line.len > 0 && !overflow).then_some(line)
+            }
+            // Backspace and delete
+            0x08 | 0x7f => {
+                self.line.len = self.line.len.saturating_sub(1);
+                None
+            }
+            byte if byte.is_ascii_graphic() || byte == b' ' => {
+                if self.line.len < LINE_LENGTH {
+                    self.line.bytes[self.line.len] = byte;
+                    self.line.len += 1;
+                } else {
+                    self.overflow = true;
+                }
+                None
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Default for LineBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Command {
+    Help,
+    Stats,
+    Tasks,
+    Deadlines,
+    Log,
+    Config,
+    // Applied from the next release of the tasks
+    Set(Setting),
+    Mode,
+    // Carried out by the mode change protocol
+    ChangeMode(SystemMode),
+    TriggerEvent,
+    Faults,
+    // Only available in firmware built with fault injection
+    AddFault(FaultWindow),
+    ClearFaults,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseError<'a> {
+    // Blank line
+    Empty,
+    UnknownCommand(&'a str),
+    Usage(&'static str),
+    UnknownParameter(&'a str),
+    UnknownMode(&'a str),
+    UnknownTask(&'a str),
+    UnknownFault(&'a str),
+    InvalidNumber(&'a str),
+    OutOfRange { number: &'a str, min: u32, max: u32 },
+}
+
+impl fmt::Display for ParseError<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Empty => Ok(()),
+            ParseError::UnknownCommand(command) => {
+                write!(f, "unknown command '{}', try 'help'", command)
+            }
+            ParseError::Usage(usage) => write!(f, "usage: {}", usage),
+            ParseError::UnknownParameter(parameter) => {
+                write!(f, "unknown parameter '{}', try 'help'", parameter)
+            }
+            ParseError::UnknownMode(mode) => write!(f, "unknown mode '{}', try 'help'", mode),
+            ParseError::UnknownTask(task) => write!(f, "unknown task '{}'", task),
+            ParseError::UnknownFault(fault) => write!(f, "unknown fault '{}', try 'help'", fault),
+            ParseError::InvalidNumber(number) => write!(f, "invalid number '{}'", number),
+            ParseError::OutOfRange { number, min, max } => {
+                write!(f, "'{}' out of range, expected {} to {}", number, min, max)
+            }
+        }
+    }
+}
+
+// Tasks are named by the mnemonic of the task set (e.g. `rp`)
+fn task(mnemonic: &str) -> Result<&'static TaskParameters, ParseError<'_>> {
+    TASKS
+        .iter()
+        .find(|task| task.mnemonic == mnemonic)
+        .ok_or(ParseError::UnknownTask(mnemonic))
+}
+
+fn number<'a, T: core::str::FromStr>(
+    word: Option<&'a str>,
+    usage: &'static str,
+) -> Result<T, ParseError<'a>> {
+    let word = word.ok_or(ParseError::Usage(usage))?;
+    word.parse().map_err(|_| ParseError::InvalidNumber(word))
+}
+
+fn bounded<'a>(
+    word: Option<&'a str>,
+    usage: &'static str,
+    range: RangeInclusive<u32>,
+) -> Result<u32, ParseError<'a>> {
+    let value = number(word, usage)?;
+    if !range.contains(&value) {
+        return Err(ParseError::OutOfRange {
+            number: word.unwrap_or_default(),
+            min: *range.start(),
+            max: *range.end(),
+        });
+    }
+    Ok(value)
+}
+
+// Fault window on the jobs `n` or `n-m` of a task
+fn fault_window<'a>(
+    mut words: impl Iterator<Item = &'a str>,
+    usage: &'static str,
+) -> Result<FaultWindow, ParseError<'a>> {
+    let task = task(words.next().ok_or(ParseError::Usage(usage))?)?;
+    let jobs = words.next().ok_or(ParseError::Usage(usage))?;
+    let (first_job, last_job) = match jobs.split_once('-') {
+        Some((first, last)) => (number(Some(first), usage)?, number(Some(last), usage)?),
+        None => (number(Some(jobs), usage)?, number(Some(jobs), usage)?),
+    };
+    if first_job > last_job {
+        return Err(ParseError::InvalidNumber(jobs));
+    }
+    let fault = match words.next() {
+        None => return Err(ParseError::Usage(usage)),
+        Some("inflate") => Fault::InflateWorkload {
+            factor: bounded(words.next(), usage, INFLATE_FACTORS)?,
+        },
+        Some("jitter") => Fault::ReleaseJitter {
+            max: bounded(words.next(), usage, RELEASE_JITTERS)?,
+        },
+        Some("no-cancel") => Fault::SuppressCancellation,
+        Some("flood") => Fault::FloodEvents {
+            interarrival: bounded(words.next(), usage, FLOOD_INTERARRIVALS)?,
+        },
+        Some(fault) => return Err(ParseError::UnknownFault(fault)),
+    };
+    Ok(FaultWindow { task: task.name, first_job, last_job, fault })
+}
+
+pub fn parse(line: &str) -> Result<Command, ParseError<'_>> {
+    let mut words = line.split_ascii_whitespace();
+    let (command, usage) = match words.next() {
+        None => return Err(ParseError::Empty),
+        Some("help") => (Command::Help, "help"),
+        Some("stats") => (Command::Stats, "stats"),
+        Some("tasks") => (Command::Tasks, "tasks"),
+        Some("deadlines") => (Command::Deadlines, "deadlines"),
+        Some("log") => (Command::Log, "log"),
+        Some("config") => (Command::Config, "config"),
+        Some("set") => {
+            const USAGE: &str = "set <parameter> <value>";
+            let setting = match words.next() {
+                None => return Err(ParseError::Usage(USAGE)),
+                Some("period") => Setting::Period(number(words.next(), USAGE)?),
+                Some("deadline") => Setting::Deadline(number(words.next(), USAGE)?),
+                Some("workload") => {
+                    const USAGE: &str = "set workload <task> <us>";
+                    let task = task(words.next().ok_or(ParseError::Usage(USAGE))?)?;
+                    Setting::Workload { task, micros: number(words.next(), USAGE)? }
+                }
+                Some("activation-condition") => {
+                    Setting::ActivationCondition(number(words.next(), USAGE)?)
+                }
+                Some("due-activation-mod") => Setting::DueActivationMod(number(words.next(), USAGE)?),
+                Some("check-due-mod") => Setting::CheckDueMod(number(words.next(), USAGE)?),
+                Some("check-due-factor") => Setting::CheckDueFactor(number(words.next(), USAGE)?),
+                Some(parameter) => return Err(ParseError::UnknownParameter(parameter)),
+            };
+            (Command::Set(setting), USAGE)
+        }
+        Some("mode") => match words.next() {
+            None => (Command::Mode, "mode"),
+            Some(mode) => {
+                let mode = SystemMode::from_name(mode).ok_or(ParseError::UnknownMode(mode))?;
+                (Command::ChangeMode(mode), "mode <mode>")
+            }
+        },
+        Some("trigger") => {
+            const USAGE: &str = "trigger event";
+            if words.next() != Some("event") {
+                return Err(ParseError::Usage(USAGE));
+            }
+            (Command::TriggerEvent, USAGE)
+        }
+        Some("faults") => (Command::Faults, "faults"),
+        Some("fault") => {
+            const USAGE: &str = "fault <task> <n>[-<m>] <fault>";
+            let mut words = words.by_ref().peekable();
+            if words.next_if_eq(&"clear").is_some() {
+                (Command::ClearFaults, "fault clear")
+            } else {
+                (Command::AddFault(fault_window(&mut words, USAGE)?), USAGE)
+            }
+        }
+        Some(command) => return Err(ParseError::UnknownCommand(command)),
+    };
+    match words.next() {
+        Some(_) => Err(ParseError::Usage(usage)),
+        None => Ok(command),
+    }
+}
+
+// Optional value, "-" when missing
+struct OrDash<T>(Option<T>);
+
+impl<T: fmt::Display> fmt::Display for OrDash<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.0 {
+            Some(value) => write!(f, "{}", value),
+            None => write!(f, "-"),
+        }
+    }
+}
+
+pub fn write_help(out: &mut impl Write) -> fmt::Result {
+    writeln!(out, "{}", HELP)
+}
+
+pub fn write_stats<const TASKS: usize>(
+    out: &mut impl Write,
+    summary: &RunSummary<TASKS>,
+    mode: SystemMode,
+) -> fmt::Result {
+    let activations: u32 = summary.deadlines.iter().map(|deadline| deadline.activations).sum();
+    writeln!(out, "uptime = {} ms, mode = {}", summary.duration, mode.name())?;
+    writeln!(out, "activations = {}, misses = {}", activations, summary.misses())?;
+    writeln!(
+        out,
+        "request buffer: pending = {}/{}, rejected = {}",
+        summary.request_buffer.current_size,
+        summary.request_buffer.capacity,
+        summary.request_buffer.rejected
+    )?;
+    writeln!(out, "activation log: activations = {}", summary.activation_log.0)?;
+    writeln!(
+        out,
+        "stack: high-water mark = {}/{} bytes",
+        summary.stack.high_water_mark, summary.stack.size
+    )
+}
+
+// Task set parameters, with the workloads of `config`
+pub fn write_tasks(out: &mut impl Write, config: &SystemConfig) -> fmt::Result {
+    for task in TASKS.iter() {
+        let activation = match task.activation {
+            Activation::Periodic { .. } => "period",
+            Activation::Sporadic { .. } => "min interarrival",
+        };
+        writeln!(
+            out,
+            "{} ({}): priority = {}, {} = {} ms, deadline = {} ms, wcet = {} us, workload = {} us",
+            task.name,
+            task.mnemonic,
+            task.priority,
+            activation,
+            task.period(),
+            task.deadline,
+            task.wcet,
+            OrDash(config.workload(task))
+        )?;
+    }
+    Ok(())
+}
+
+pub fn write_config(out: &mut impl Write, config: &SystemConfig) -> fmt::Result {
+    writeln!(out, "period = {} ms, deadline = {} ms", config.period, config.deadline)?;
+    writeln!(
+        out,
+        "workloads: rp = {} us, ocp = {} us, alr = {} us",
+        config.regular_producer_workload,
+        config.on_call_producer_workload,
+        config.activation_log_reader_workload
+    )?;
+    writeln!(
+        out,
+        "activation condition = {}, due activation mod = {}, check due mod = {}, \
+         check due factor = {}",
+        config.activation_condition,
+        config.moduli.due_activation,
+        config.moduli.check_due,
+        config.moduli.check_due_factor
+    )
+}
+
+pub fn write_mode(
+    out: &mut impl Write,
+    mode: SystemMode,
+    pending: Option<ModeChangeRequest>,
+) -> fmt::Result {
+    let parameters = mode.parameters();
+    writeln!(
+        out,
+        "mode = {}: on call producer = {}, load factor = {}, period factor = {}",
+        mode.name(),
+        if parameters.on_call_producer { "on" } else { "off" },
+        parameters.load_factor,
+        parameters.period_factor
+    )?;
+    match pending {
+        Some(request) => writeln!(
+            out,
+            "change to {} pending ({:?}), waiting for the jobs in flight",
+            request.mode.name(),
+            request.trigger
+        ),
+        None => Ok(()),
+    }
+}
+
+pub fn write_deadlines<const TASKS: usize>(
+    out: &mut impl Write,
+    summary: &RunSummary<TASKS>,
+) -> fmt::Result {
+    for deadline in summary.deadlines.iter() {
+        writeln!(
+            out,
+            "{}: activations = {}, misses = {}, skipped = {}, longest miss run = {}, \
+             max lateness = {} us, max response time = {} us, max execution time = {} us",
+            deadline.name,
+            deadline.activations,
+            deadline.misses,
+            deadline.skipped,
+            deadline.lateness.longest_miss_run,
+            OrDash(deadline.lateness.max),
+            OrDash(deadline.response_time.max().map(cycles_to_micros)),
+            OrDash(deadline.execution_time.max().map(cycles_to_micros))
+        )?;
+    }
+    Ok(())
+}
+
+pub fn write_faults<'a>(
+    out: &mut impl Write,
+    windows: impl Iterator<Item = &'a FaultWindow>,
+) -> fmt::Result {
+    let mut empty = true;
+    for window in windows {
+        empty = false;
+        write!(out, "{}: jobs {}-{}, ", window.task, window.first_job, window.last_job)?;
+        match window.fault {
+            Fault::InflateWorkload { factor } => writeln!(out, "workload x{}", factor)?,
+            Fault::ReleaseJitter { max } => writeln!(out, "release jitter up to {} ms", max)?,
+            Fault::SuppressCancellation => writeln!(out, "deadline not cancelled")?,
+            Fault::FloodEvents { interarrival } => {
+                writeln!(out, "external events every {} ms", interarrival)?
+            }
+        }
+    }
+    if empty {
+        writeln!(out, "no faults")?;
+    }
+    Ok(())
+}
+
+// `interarrival` is the observed (min, max) interarrival of the events
+pub fn write_log<'a>(
+    out: &mut impl Write,
+    entries: impl Iterator<Item = &'a LogEntry>,
+    interarrival: Option<(Duration, Duration)>,
+) -> fmt::Result {
+    for entry in entries {
+        write!(
+            out,
+            "#{}: at {} ms",
+            entry.sequence,
+            entry.time.duration_since_epoch().to_millis()
+        )?;
+        match entry.interarrival {
+            Some(interarrival) => writeln!(out, ", interarrival = {} ms", interarrival.to_millis())?,
+            None => writeln!(out)?,
+        }
+    }
+    match interarrival {
+        Some((min, max)) => writeln!(
+            out,
+            "interarrival: min = {} ms, max = {} ms",
+            min.to_millis(),
+            max.to_millis()
+        ),
+        None => writeln!(out, "interarrival: -"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        deadline::{DeadlineProtectedObject, MissPolicy},
+        request_buffer::RequestBufferStatistics,
+        stack::{LEVELS, StackUsage},
+        task_set::{ACTIVATION_LOG_READER, REGULAR_PRODUCER},
+        time::Instant,
+    };
+
+    fn line(buffer: &mut LineBuffer, text: &str) -> Option<Line> {
+        text.bytes().filter_map(|byte| buffer.push(byte)).last()
+    }
+
+    #[test]
+    fn lines_are_split_on_any_terminator() {
+        let mut buffer = LineBuffer::new();
+        assert_eq!(line(&mut buffer, "stats\r\n").unwrap().as_str(), "stats");
+        assert_eq!(line(&mut buffer, "log\n").unwrap().as_str(), "log");
+        assert_eq!(line(&mut buffer, "tasks"), None);
+        assert_eq!(buffer.push(b'\r').unwrap().as_str(), "tasks");
+    }
+
+    #[test]
+    fn backspace_erases_and_long_lines_are_dropped() {
+        let mut buffer = LineBuffer::new();
+        assert_eq!(line(&mut buffer, "logs\x08\r").unwrap().as_str(), "log");
+
+        let long = [b'x'; LINE_LENGTH + 1];
+        assert!(long.iter().all(|&byte| buffer.push(byte).is_none()));
+        assert_eq!(buffer.push(b'\n'), None);
+        assert_eq!(line(&mut buffer, "help\n").unwrap().as_str(), "help");
+    }
+
+    #[test]
+    fn commands_are_parsed() {
+        assert_eq!(parse("stats"), Ok(Command::Stats));
+        assert_eq!(parse("  deadlines "), Ok(Command::Deadlines));
+        assert_eq!(parse("trigger event"), Ok(Command::TriggerEvent));
+        assert_eq!(
+            parse("set workload rp 900"),
+            Ok(Command::Set(Setting::Workload { task: &REGULAR_PRODUCER, micros: 900 }))
+        );
+        assert_eq!(parse("set period 2000"), Ok(Command::Set(Setting::Period(2_000))));
+        assert_eq!(parse("mode"), Ok(Command::Mode));
+        assert_eq!(parse("mode high-load"), Ok(Command::ChangeMode(SystemMode::HighLoad)));
+        assert_eq!(
+            parse("set check-due-factor 4"),
+            Ok(Command::Set(Setting::CheckDueFactor(4)))
+        );
+        assert_eq!(parse("fault clear"), Ok(Command::ClearFaults));
+        assert_eq!(
+            parse("fault alr 2 inflate 100"),
+            Ok(Command::AddFault(FaultWindow {
+                task: ACTIVATION_LOG_READER.name,
+                first_job: 2,
+                last_job: 2,
+                fault: Fault::InflateWorkload { factor: 100 },
+            }))
+        );
+        assert_eq!(
+            parse("fault rp 3-5 no-cancel"),
+            Ok(Command::AddFault(FaultWindow {
+                task: REGULAR_PRODUCER.name,
+                first_job: 3,
+                last_job: 5,
+                fault: Fault::SuppressCancellation,
+            }))
+        );
+    }
+
+    #[test]
+    fn malformed_commands_are_reported() {
+        assert_eq!(parse(" "), Err(ParseError::Empty));
+        assert_eq!(parse("reboot"), Err(ParseError::UnknownCommand("reboot")));
+        assert_eq!(parse("stats now"), Err(ParseError::Usage("stats")));
+        assert_eq!(parse("set workload xx 1"), Err(ParseError::UnknownTask("xx")));
+        assert_eq!(parse("set workload rp -1"), Err(ParseError::InvalidNumber("-1")));
+        assert!(matches!(parse("set workload rp"), Err(ParseError::Usage(_))));
+        assert!(matches!(parse("set period"), Err(ParseError::Usage(_))));
+        assert_eq!(parse("set priority 3"), Err(ParseError::UnknownParameter("priority")));
+        assert_eq!(parse("mode turbo"), Err(ParseError::UnknownMode("turbo")));
+        assert!(matches!(parse("mode nominal now"), Err(ParseError::Usage(_))));
+        assert!(matches!(parse("trigger"), Err(ParseError::Usage(_))));
+        assert_eq!(parse("fault ees 4-8 crash"), Err(ParseError::UnknownFault("crash")));
+        assert_eq!(parse("fault ees 8-4 flood 10"), Err(ParseError::InvalidNumber("8-4")));
+        assert!(matches!(parse("fault ees 4-8 flood"), Err(ParseError::Usage(_))));
+        assert_eq!(
+            parse("fault ees 4-8 flood 0"),
+            Err(ParseError::OutOfRange { number: "0", min: 1, max: 1_000 })
+        );
+        assert!(matches!(parse("fault rp 1 inflate 0"), Err(ParseError::OutOfRange { .. })));
+        assert!(matches!(parse("fault rp 1 inflate 5000"), Err(ParseError::OutOfRange { .. })));
+        assert!(matches!(parse("fault ocp 1 jitter 4294967295"), Err(ParseError::OutOfRange { .. })));
+        assert!(matches!(parse("fault clear all"), Err(ParseError::Usage(_))));
+    }
+
+    #[test]
+    fn reports_list_every_task() {
+        let mut dpo = DeadlineProtectedObject::new(REGULAR_PRODUCER.name, MissPolicy::LogOnly);
+        let job = dpo.release();
+        dpo.deadline_missed(job, Instant::from_ticks(0));
+        let summary = RunSummary {
+            duration: 1_500,
+            deadlines: [dpo.statistics()],
+            activation_log: (0, None),
+            request_buffer: RequestBufferStatistics { current_size: 1, capacity: 5, rejected: 0 },
+            stack: StackUsage { size: 4096, high_water_mark: 1024, levels: [0; LEVELS] },
+        };
+
+        let mut out = String::new();
+        write_stats(&mut out, &summary, SystemMode::Nominal).unwrap();
+        assert!(out.contains("uptime = 1500 ms, mode = nominal"));
+        assert!(out.contains("activations = 1, misses = 1"));
+        assert!(out.contains("stack: high-water mark = 1024/4096 bytes"));
+
+        out.clear();
+        write_deadlines(&mut out, &summary).unwrap();
+        assert!(out.starts_with("regular_producer: activations = 1, misses = 1, skipped = 0"));
+
+        out.clear();
+        write_tasks(&mut out, &SystemConfig::DEFAULT).unwrap();
+        assert_eq!(out.lines().count(), TASKS.len());
+        assert!(out.contains("external_event_server (ees): priority = 11"));
+        assert!(out.contains("workload = 8000 us"));
+        assert!(out.contains("workload = -"));
+
+        out.clear();
+        write_config(&mut out, &SystemConfig::DEFAULT).unwrap();
+        assert!(out.starts_with("period = 1000 ms, deadline = 500 ms"));
+
+        out.clear();
+        let window = FaultWindow {
+            task: REGULAR_PRODUCER.name,
+            first_job: 3,
+            last_job: 3,
+            fault: Fault::SuppressCancellation,
+        };
+        write_faults(&mut out, [window].iter()).unwrap();
+        assert_eq!(out, "regular_producer: jobs 3-3, deadline not cancelled\n");
+        out.clear();
+        write_faults(&mut out, [].iter()).unwrap();
+        assert_eq!(out, "no faults\n");
+    }
+
+    #[test]
+    fn log_report_shows_interarrivals() {
+        let at = |millis: u32| Instant::from_ticks(0) + Duration::millis(1) * millis;
+        let entries = [
+            LogEntry { sequence: 1, time: at(10), interarrival: None },
+            LogEntry { sequence: 2, time: at(30), interarrival: Some(Duration::millis(1) * 20) },
+        ];
+        let mut out = String::new();
+        write_log(&mut out, entries.iter(), Some((Duration::millis(1) * 20, Duration::millis(1) * 20)))
+            .unwrap();
+        assert_eq!(
+            out,
+            "#1: at 10 ms\n\
+             #2: at 30 ms, interarrival = 20 ms\n\
+             interarrival: min = 20 ms, max = 20 ms\n"
+        );
+    }
+}
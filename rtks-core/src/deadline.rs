@@ -0,0 +1,463 @@
+use crate::{
+    interarrival::{Admission, InterarrivalGuard},
+    time::{self, Instant},
+    timing::{JobTimestamps, TimingStatistics},
+};
+
+// Upper bounds (microseconds, exclusive) of the lateness histogram buckets,
+// the last bucket collects the jobs later than 1 s
+pub const LATENESS_BUCKETS: [u32; 4] = [1_000, 10_000, 100_000, 1_000_000];
+
+// Late jobs of the task whose completion is awaited
+const OVERDUE_JOBS: usize = 2;
+
+// What to do when a deadline of the task is missed
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum MissPolicy {
+    // Only count and report the miss
+    LogOnly,
+    // Ask the late job to give up its remaining work at its next abort point
+    AbortJob,
+    // Drop the release following the miss, to let the task catch up
+    SkipNextRelease,
+    // Switch the whole system into the degraded mode
+    DegradedMode,
+    // Log only, until `consecutive_misses` deadlines in a row are missed
+    Escalate {
+        consecutive_misses: u32,
+        escalation: Escalation,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum Escalation {
+    Panic,
+    Reset,
+}
+
+// System-level reaction to a miss, carried out by the watchdog
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum MissAction {
+    None,
+    EnterDegradedMode,
+    Panic,
+    Reset,
+}
+
+// SHARED RESOURCE FOR HANDLING DEADLINE
+// Every release of the task is a job with its own id, the deadline timer tells
+// the object whether the deadline of each job was met or missed.
+pub struct DeadlineProtectedObject {
+    name: &'static str,
+    policy: MissPolicy,
+    misses: u32,
+    consecutive_misses: u32,
+    activations: u32,
+    overdue: [Option<(u32, Instant)>; OVERDUE_JOBS],
+    lateness: LatenessStatistics,
+    abort_job: Option<u32>,
+    skip_next_release: bool,
+    skipped: u32,
+    response_time: TimingStatistics,
+    execution_time: TimingStatistics,
+    release_jitter: TimingStatistics,
+    // Sporadic tasks only
+    interarrival: Option<InterarrivalGuard>,
+}
+
+impl DeadlineProtectedObject {
+    pub fn new(
+    name: &'static str,
+    policy: MissPolicy,
+    ) -> Self {
+        DeadlineProtectedObject {
+            name,
+            policy,
+            misses: 0,
+            consecutive_misses: 0,
+            activations: 0,
+            overdue: [None; OVERDUE_JOBS],
+            lateness: LatenessStatistics::new(),
+            abort_job: None,
+            skip_next_release: false,
+            skipped: 0,
+            response_time: TimingStatistics::new(),
+            execution_time: TimingStatistics::new(),
+            release_jitter: TimingStatistics::new(),
+            interarrival: None,
+        }
+    }
+
+    // Enforce the minimum interarrival of a sporadic task on its releases
+    pub fn with_interarrival(mut self, guard: InterarrivalGuard) -> Self {
+        self.interarrival = Some(guard);
+        self
+    }
+
+    // Arrival of a release of the task, to be admitted before the job is released
+    pub fn admit(&mut self, arrival: Instant) -> Admission {
+        let Some(guard) = self.interarrival.as_mut() else {
+            return Admission::Release(arrival);
+        };
+        let admission = guard.admit(arrival);
+        if admission != Admission::Release(arrival) {
+            defmt::warn!(
+                "Release of task '{}' earlier than its minimum interarrival: {}",
+                self.name,
+                guard.policy()
+            );
+        }
+        admission
+    }
+
+    // New release of the task, returns the id of the job
+    pub fn release(&mut self) -> u32 {
+        self.activations += 1;
+        self.activations
+    }
+
+    pub fn deadline_met(&mut self, job: u32) {
+        self.consecutive_misses = 0;
+        defmt::info!(
+            "Deadline of job {} of task '{}' was cancelled",
+            job,
+            self.name
+        );
+    }
+
+    // `deadline` is the absolute deadline of the job, still running at this point
+    pub fn deadline_missed(&mut self, job: u32, deadline: Instant) -> MissAction {
+        self.misses += 1;
+        self.consecutive_misses += 1;
+        self.lateness.longest_miss_run = self.lateness.longest_miss_run.max(self.consecutive_misses);
+        // Forget the oldest late job if too many are pending, its lateness stays unknown
+        self.overdue.rotate_left(1);
+        self.overdue[OVERDUE_JOBS - 1] = Some((job, deadline));
+        defmt::error!(
+            "Deadline miss detected for job {} of task '{}'. Misses: {}, Activations: {}",
+            job,
+            self.name,
+            self.misses,
+            self.activations
+        );
+        self.apply_policy(job)
+    }
+
+    // Completion of a job after its deadline expired, returns how late it was
+    // (microseconds) or None if the job is not known to be late
+    pub fn late_completion(&mut self, job: u32, now: Instant) -> Option<u32> {
+        let overdue = self
+            .overdue
+            .iter_mut()
+            .find(|overdue| overdue.is_some_and(|(id, _)| id == job))?;
+        let (_, deadline) = overdue.take()?;
+        let lateness = time::to_micros(now - deadline);
+        self.lateness.record(lateness);
+        defmt::warn!(
+            "Job {} of task '{}' completed {} us late",
+            job,
+            self.name,
+            lateness
+        );
+        Some(lateness)
+    }
+
+    fn apply_policy(&mut self, job: u32) -> MissAction {
+        match self.policy {
+            MissPolicy::LogOnly => MissAction::None,
+            MissPolicy::AbortJob => {
+                self.abort_job = Some(job);
+                MissAction::None
+            }
+            MissPolicy::SkipNextRelease => {
+                self.skip_next_release = true;
+                MissAction::None
+            }
+            MissPolicy::DegradedMode => MissAction::EnterDegradedMode,
+            MissPolicy::Escalate { consecutive_misses, escalation } => {
+                if self.consecutive_misses < consecutive_misses {
+                    MissAction::None
+                } else {
+                    defmt::error!(
+                        "Task '{}' missed {} deadlines in a row, escalating to {}",
+                        self.name,
+                        self.consecutive_misses,
+                        escalation
+                    );
+                    match escalation {
+                        Escalation::Panic => MissAction::Panic,
+                        Escalation::Reset => MissAction::Reset,
+                    }
+                }
+            }
+        }
+    }
+
+    // Called by the task when it is released, returns false when the job has
+    // to be skipped because of the miss policy
+    pub fn begin_job(&mut self) -> bool {
+        if self.skip_next_release {
+            self.skip_next_release = false;
+            self.skipped += 1;
+            defmt::warn!("Release of task '{}' skipped", self.name);
+            return false;
+        }
+        true
+    }
+
+    // Polled by the task at the abort points of `job`
+    pub fn abort_requested(&self, job: u32) -> bool {
+        self.abort_job == Some(job)
+    }
+
+    pub fn job_completed(&mut self, job: JobTimestamps) {
+        self.response_time.record(job.response_time());
+        self.execution_time.record(job.execution_time());
+        self.release_jitter.record(job.release_jitter());
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn misses(&self) -> u32 {
+        self.misses
+    }
+
+    pub fn activations(&self) -> u32 {
+        self.activations
+    }
+
+    pub fn policy(&self) -> MissPolicy {
+        self.policy
+    }
+
+    pub fn statistics(&self) -> DeadlineStatistics {
+        DeadlineStatistics {
+            name: self.name,
+            activations: self.activations,
+            misses: self.misses,
+            skipped: self.skipped,
+            response_time: self.response_time,
+            execution_time: self.execution_time,
+            release_jitter: self.release_jitter,
+            interarrival_violations: self.interarrival.map_or(0, |guard| guard.violations()),
+            lateness: LatenessStatistics {
+                running: self.overdue.iter().flatten().count() as u32,
+                ..self.lateness
+            },
+        }
+    }
+}
+
+// How late the missed deadlines of a task were, measured at job completion
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LatenessStatistics {
+    // Completed late jobs, by LATENESS_BUCKETS
+    pub histogram: [u32; LATENESS_BUCKETS.len() + 1],
+    // Microseconds
+    pub max: Option<u32>,
+    // Late jobs not completed yet
+    pub running: u32,
+    pub longest_miss_run: u32,
+}
+
+impl LatenessStatistics {
+    pub const fn new() -> Self {
+        LatenessStatistics {
+            histogram: [0; LATENESS_BUCKETS.len() + 1],
+            max: None,
+            running: 0,
+            longest_miss_run: 0,
+        }
+    }
+
+    pub fn record(&mut self, lateness: u32) {
+        let bucket = LATENESS_BUCKETS
+            .iter()
+            .position(|&bound| lateness < bound)
+            .unwrap_or(LATENESS_BUCKETS.len());
+        self.histogram[bucket] += 1;
+        self.max = self.max.max(Some(lateness));
+    }
+}
+
+impl Default for LatenessStatistics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl defmt::Format for LatenessStatistics {
+    fn format(&self, f: defmt::Formatter) {
+        match self.max {
+            Some(max) => defmt::write!(f, "max lateness = {} us", max),
+            None => defmt::write!(f, "max lateness = -"),
+        }
+        defmt::write!(
+            f,
+            ", still running = {}, longest miss run = {}, histogram (<1 ms, <10 ms, <100 ms, <1 s, >=1 s) = {}",
+            self.running,
+            self.longest_miss_run,
+            self.histogram
+        );
+    }
+}
+
+// Snapshot of the counters of a deadline protected object
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub struct DeadlineStatistics {
+    pub name: &'static str,
+    pub activations: u32,
+    pub misses: u32,
+    // Releases dropped by the SkipNextRelease policy, neither met nor missed
+    pub skipped: u32,
+    pub response_time: TimingStatistics,
+    pub execution_time: TimingStatistics,
+    pub release_jitter: TimingStatistics,
+    pub interarrival_violations: u32,
+    pub lateness: LatenessStatistics,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{interarrival::EarlyRelease, task_set::ON_CALL_PRODUCER, time::Duration};
+
+    #[test]
+    fn releases_get_consecutive_job_ids() {
+        let mut dpo = DeadlineProtectedObject::new("Task", MissPolicy::LogOnly);
+        assert_eq!(dpo.release(), 1);
+        assert_eq!(dpo.release(), 2);
+        assert_eq!(dpo.activations(), 2);
+    }
+
+    #[test]
+    fn only_missed_deadlines_are_counted() {
+        let mut dpo = DeadlineProtectedObject::new("Task", MissPolicy::LogOnly);
+        let first = dpo.release();
+        dpo.deadline_met(first);
+        let second = dpo.release();
+        dpo.deadline_missed(second, Instant::from_ticks(0));
+
+        assert_eq!(dpo.misses(), 1);
+        assert_eq!(dpo.activations(), 2);
+    }
+
+    #[test]
+    fn early_releases_are_counted_for_sporadic_tasks_only() {
+        let arrival = Instant::from_ticks(0);
+        let mut periodic = DeadlineProtectedObject::new("Periodic", MissPolicy::LogOnly);
+        let mut sporadic = DeadlineProtectedObject::new("Sporadic", MissPolicy::LogOnly)
+            .with_interarrival(InterarrivalGuard::new(&ON_CALL_PRODUCER, EarlyRelease::Reject));
+        for dpo in [&mut periodic, &mut sporadic] {
+            assert_eq!(dpo.admit(arrival), Admission::Release(arrival));
+        }
+
+        assert_eq!(periodic.admit(arrival), Admission::Release(arrival));
+        assert_eq!(sporadic.admit(arrival), Admission::Rejected);
+        assert_eq!(periodic.statistics().interarrival_violations, 0);
+        assert_eq!(sporadic.statistics().interarrival_violations, 1);
+    }
+
+    #[test]
+    fn completed_jobs_update_timing_statistics() {
+        let mut dpo = DeadlineProtectedObject::new("Task", MissPolicy::LogOnly);
+        dpo.job_completed(JobTimestamps { release: 0, start: 10, completion: 110 });
+        dpo.job_completed(JobTimestamps { release: 1_000, start: 1_050, completion: 1_250 });
+
+        let statistics = dpo.statistics();
+        assert_eq!(statistics.response_time.max(), Some(250));
+        assert_eq!(statistics.execution_time.min(), Some(100));
+        assert_eq!(statistics.execution_time.max(), Some(200));
+        assert_eq!(statistics.release_jitter.max(), Some(50));
+    }
+
+    fn miss(dpo: &mut DeadlineProtectedObject) -> MissAction {
+        let job = dpo.release();
+        dpo.deadline_missed(job, Instant::from_ticks(0))
+    }
+
+    fn meet(dpo: &mut DeadlineProtectedObject) {
+        let job = dpo.release();
+        dpo.deadline_met(job);
+    }
+
+    #[test]
+    fn abort_policy_flags_the_late_job_only() {
+        let mut dpo = DeadlineProtectedObject::new("Task", MissPolicy::AbortJob);
+        let late = dpo.release();
+        assert_eq!(dpo.deadline_missed(late, Instant::from_ticks(0)), MissAction::None);
+        assert!(dpo.abort_requested(late));
+        // The next job is not affected
+        let next = dpo.release();
+        assert!(!dpo.abort_requested(next));
+    }
+
+    #[test]
+    fn skip_policy_drops_one_release() {
+        let mut dpo = DeadlineProtectedObject::new("Task", MissPolicy::SkipNextRelease);
+        miss(&mut dpo);
+        assert!(!dpo.begin_job());
+        assert!(dpo.begin_job());
+        assert_eq!(dpo.statistics().skipped, 1);
+    }
+
+    #[test]
+    fn degraded_policy_asks_for_mode_change() {
+        let mut dpo = DeadlineProtectedObject::new("Task", MissPolicy::DegradedMode);
+        meet(&mut dpo);
+        assert_eq!(miss(&mut dpo), MissAction::EnterDegradedMode);
+    }
+
+    #[test]
+    fn escalation_after_consecutive_misses_only() {
+        let mut dpo = DeadlineProtectedObject::new(
+            "Task",
+            MissPolicy::Escalate { consecutive_misses: 2, escalation: Escalation::Reset },
+        );
+        assert_eq!(miss(&mut dpo), MissAction::None);
+        // A met deadline breaks the run of misses
+        meet(&mut dpo);
+        assert_eq!(miss(&mut dpo), MissAction::None);
+        assert_eq!(miss(&mut dpo), MissAction::Reset);
+    }
+
+    #[test]
+    fn lateness_is_measured_at_completion() {
+        let mut dpo = DeadlineProtectedObject::new("Task", MissPolicy::LogOnly);
+        let deadline = Instant::from_ticks(0) + Duration::millis(100);
+        let job = dpo.release();
+        dpo.deadline_missed(job, deadline);
+        assert_eq!(dpo.statistics().lateness.running, 1);
+
+        assert_eq!(dpo.late_completion(job, deadline + Duration::millis(5)), Some(5_000));
+        // Completion is only accounted once
+        assert_eq!(dpo.late_completion(job, deadline + Duration::millis(6)), None);
+
+        let lateness = dpo.statistics().lateness;
+        assert_eq!(lateness.running, 0);
+        assert_eq!(lateness.max, Some(5_000));
+        assert_eq!(lateness.histogram, [0, 1, 0, 0, 0]);
+    }
+
+    #[test]
+    fn lateness_histogram_buckets() {
+        let mut lateness = LatenessStatistics::new();
+        for micros in [0, 999, 1_000, 250_000, 5_000_000] {
+            lateness.record(micros);
+        }
+        assert_eq!(lateness.histogram, [2, 1, 0, 1, 1]);
+        assert_eq!(lateness.max, Some(5_000_000));
+    }
+
+    #[test]
+    fn longest_run_of_consecutive_misses() {
+        let mut dpo = DeadlineProtectedObject::new("Task", MissPolicy::LogOnly);
+        miss(&mut dpo);
+        miss(&mut dpo);
+        meet(&mut dpo);
+        miss(&mut dpo);
+        assert_eq!(dpo.statistics().lateness.longest_miss_run, 2);
+    }
+}
@@ -0,0 +1,276 @@
+use crate::{
+    deadline::{DeadlineProtectedObject, DeadlineStatistics, MissAction},
+    time::Instant,
+    Notify,
+};
+
+// Identifies the deadline protected object of a task within the timer
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub struct DeadlineHandle(usize);
+
+// A release of a task, cancelled by the task when the job completes
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub struct JobId {
+    handle: DeadlineHandle,
+    id: u32,
+}
+
+impl JobId {
+    pub fn handle(&self) -> DeadlineHandle {
+        self.handle
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct ArmedDeadline {
+    at: Instant,
+    job: JobId,
+}
+
+// Single timer service for the deadlines of the whole task set: armed deadlines
+// are kept in a queue ordered by expiry, the timer task sleeps until the head
+// expires and `waker` is notified when a new deadline becomes the head.
+// Each job has its own entry, so up to DEPTH deadlines can be armed at once,
+// including several jobs of the same task released back to back.
+pub struct DeadlineTimer<W: Notify, const TASKS: usize, const DEPTH: usize> {
+    objects: [DeadlineProtectedObject; TASKS],
+    queue: [Option<ArmedDeadline>; DEPTH],
+    armed: usize,
+    waker: W,
+}
+
+impl<W: Notify, const TASKS: usize, const DEPTH: usize> DeadlineTimer<W, TASKS, DEPTH> {
+    pub fn new(objects: [DeadlineProtectedObject; TASKS], waker: W) -> Self {
+        DeadlineTimer {
+            objects,
+            queue: [None; DEPTH],
+            armed: 0,
+            waker,
+        }
+    }
+
+    // Handles of the objects, in the order they were given to `new`
+    pub fn handles() -> [DeadlineHandle; TASKS] {
+        core::array::from_fn(DeadlineHandle)
+    }
+
+    pub fn object(&mut self, handle: DeadlineHandle) -> &mut DeadlineProtectedObject {
+        &mut self.objects[handle.0]
+    }
+
+    // Release a new job of the task, with its absolute deadline `at`
+    pub fn arm(&mut self, handle: DeadlineHandle, at: Instant) -> JobId {
+        let job = JobId {
+            handle,
+            id: self.objects[handle.0].release(),
+        };
+        if self.armed == DEPTH {
+            defmt::error!(
+                "Deadline queue full, job {} of task '{}' is not supervised",
+                job.id,
+                self.objects[handle.0].name()
+            );
+            return job;
+        }
+
+        let position = self.queue[..self.armed]
+            .iter()
+            .position(|armed| armed.is_some_and(|armed| armed.at > at))
+            .unwrap_or(self.armed);
+        self.queue[position..=self.armed].rotate_right(1);
+        self.queue[position] = Some(ArmedDeadline { at, job });
+        self.armed += 1;
+
+        // The timer task is sleeping until a later deadline
+        if position == 0 {
+            self.waker.notify();
+        }
+        job
+    }
+
+    // Job completed at `now`, returns false if its deadline had already expired
+    pub fn cancel(&mut self, job: JobId, now: Instant) -> bool {
+        if !self.remove(job) {
+            self.objects[job.handle.0].late_completion(job.id, now);
+            return false;
+        }
+        self.objects[job.handle.0].deadline_met(job.id);
+        true
+    }
+
+    // Release dropped by the miss policy: its deadline is withdrawn without
+    // being met, so the run of consecutive misses goes on
+    pub fn skip(&mut self, job: JobId) {
+        self.remove(job);
+    }
+
+    pub fn next_expiry(&self) -> Option<Instant> {
+        self.queue[0].map(|armed| armed.at)
+    }
+
+    // Pop one deadline expired at `now`, to be called until it returns None
+    pub fn expire(&mut self, now: Instant) -> Option<MissAction> {
+        let head = self.queue[0].filter(|armed| armed.at <= now)?;
+        self.remove(head.job);
+        Some(self.objects[head.job.handle.0].deadline_missed(head.job.id, head.at))
+    }
+
+    pub fn statistics(&self) -> [DeadlineStatistics; TASKS] {
+        core::array::from_fn(|index| self.objects[index].statistics())
+    }
+
+    fn remove(&mut self, job: JobId) -> bool {
+        let Some(position) = self.queue[..self.armed]
+            .iter()
+            .position(|armed| armed.is_some_and(|armed| armed.job == job))
+        else {
+            return false;
+        };
+        self.queue[position..self.armed].rotate_left(1);
+        self.armed -= 1;
+        self.queue[self.armed] = None;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{deadline::MissPolicy, mock::CountingNotify, time::Duration};
+
+    type Timer = DeadlineTimer<CountingNotify, 3, 4>;
+
+    fn timer() -> Timer {
+        DeadlineTimer::new(
+            [
+                DeadlineProtectedObject::new("A", MissPolicy::LogOnly),
+                DeadlineProtectedObject::new("B", MissPolicy::LogOnly),
+                DeadlineProtectedObject::new("C", MissPolicy::DegradedMode),
+            ],
+            CountingNotify::new(),
+        )
+    }
+
+    // Milliseconds after the epoch
+    fn at(millis: u8) -> Instant {
+        Instant::from_ticks(0) + Duration::millis(millis.into())
+    }
+
+    #[test]
+    fn deadlines_expire_in_order() {
+        let mut timer = timer();
+        let [a, b, c] = Timer::handles();
+        timer.arm(b, at(30));
+        timer.arm(a, at(10));
+        timer.arm(c, at(20));
+        assert_eq!(timer.next_expiry(), Some(at(10)));
+
+        assert_eq!(timer.expire(at(25)), Some(MissAction::None));
+        assert_eq!(timer.expire(at(25)), Some(MissAction::EnterDegradedMode));
+        assert_eq!(timer.expire(at(25)), None);
+        assert_eq!(timer.next_expiry(), Some(at(30)));
+
+        assert_eq!(timer.object(a).misses(), 1);
+        assert_eq!(timer.object(b).misses(), 0);
+        assert_eq!(timer.object(c).misses(), 1);
+    }
+
+    #[test]
+    fn cancelled_deadline_never_expires() {
+        let mut timer = timer();
+        let [a, b, _] = Timer::handles();
+        let job = timer.arm(a, at(10));
+        timer.arm(b, at(20));
+
+        assert!(timer.cancel(job, at(5)));
+        assert!(!timer.cancel(job, at(5)));
+        assert_eq!(timer.next_expiry(), Some(at(20)));
+        assert_eq!(timer.expire(at(15)), None);
+        assert_eq!(timer.statistics()[0].misses, 0);
+        assert_eq!(timer.statistics()[0].activations, 1);
+    }
+
+    #[test]
+    fn skipped_release_is_neither_met_nor_missed() {
+        let mut timer: DeadlineTimer<CountingNotify, 1, 2> = DeadlineTimer::new(
+            [DeadlineProtectedObject::new("A", MissPolicy::SkipNextRelease)],
+            CountingNotify::new(),
+        );
+        let [a] = DeadlineTimer::<CountingNotify, 1, 2>::handles();
+        timer.arm(a, at(10));
+        timer.expire(at(10));
+
+        let skipped = timer.arm(a, at(20));
+        assert!(!timer.object(a).begin_job());
+        timer.skip(skipped);
+        assert_eq!(timer.next_expiry(), None);
+
+        timer.arm(a, at(30));
+        timer.expire(at(30));
+        let [statistics] = timer.statistics();
+        assert_eq!((statistics.misses, statistics.skipped), (2, 1));
+        assert_eq!(statistics.lateness.longest_miss_run, 2);
+    }
+
+    #[test]
+    fn waker_is_notified_of_new_head_only() {
+        let mut timer = timer();
+        let [a, b, c] = Timer::handles();
+        timer.arm(a, at(20));
+        timer.arm(b, at(30));
+        assert_eq!(timer.waker.0, 1);
+        timer.arm(c, at(10));
+        assert_eq!(timer.waker.0, 2);
+    }
+
+    #[test]
+    fn back_to_back_sporadic_releases_are_tracked_separately() {
+        let mut timer = timer();
+        let [a, _, _] = Timer::handles();
+        // Second release before the deadline of the first one
+        let first = timer.arm(a, at(10));
+        let second = timer.arm(a, at(15));
+        assert_ne!(first, second);
+
+        // The first job is late, the second completes in time
+        assert_eq!(timer.expire(at(10)), Some(MissAction::None));
+        assert!(timer.cancel(second, at(12)));
+        assert_eq!(timer.expire(at(20)), None);
+
+        let statistics = timer.object(a).statistics();
+        assert_eq!(statistics.activations, 2);
+        assert_eq!(statistics.misses, 1);
+    }
+
+    #[test]
+    fn late_completion_is_attributed_to_its_own_job() {
+        let mut timer = timer();
+        let [a, _, _] = Timer::handles();
+        let first = timer.arm(a, at(10));
+        timer.expire(at(10));
+        let second = timer.arm(a, at(30));
+
+        // Completion of the first job after its deadline must not cancel the second one
+        assert!(!timer.cancel(first, at(20)));
+        assert_eq!(timer.next_expiry(), Some(at(30)));
+        assert!(timer.cancel(second, at(25)));
+        assert_eq!(timer.object(a).misses(), 1);
+        assert_eq!(timer.object(a).statistics().lateness.max, Some(10_000));
+    }
+
+    #[test]
+    fn full_queue_does_not_lose_armed_deadlines() {
+        let mut timer = timer();
+        let [a, b, _] = Timer::handles();
+        for millis in [10, 20, 30, 40] {
+            timer.arm(a, at(millis));
+        }
+        let unsupervised = timer.arm(b, at(5));
+        assert_eq!(timer.next_expiry(), Some(at(10)));
+        assert!(!timer.cancel(unsupervised, at(1)));
+    }
+}
@@ -0,0 +1,250 @@
+// Faults injected into the task set to exercise the deadline machinery,
+// active on a window of jobs of one task so that the expected misses are
+// known in advance. Used by the firmware `fault-injection` feature.
+
+use core::ops::RangeInclusive;
+
+const MAX_FAULTS: usize = 4;
+
+// Arguments accepted from the console. The workload stays within u32
+// microseconds, a 0 ms interarrival cannot start the timer of the external
+// interrupt and a longer one is no flood
+pub const INFLATE_FACTORS: RangeInclusive<u32> = 1..=1_000;
+pub const RELEASE_JITTERS: RangeInclusive<u32> = 0..=10_000;
+pub const FLOOD_INTERARRIVALS: RangeInclusive<u32> = 1..=1_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum Fault {
+    // Multiply the workload of each job
+    InflateWorkload { factor: u32 },
+    // Delay the start of each job by a pseudo-random time in [0, max] milliseconds
+    ReleaseJitter { max: u32 },
+    // Complete the job without cancelling its deadline
+    SuppressCancellation,
+    // Raise the external interrupt every `interarrival` milliseconds, the jobs
+    // are then the external interrupts instead of the server releases
+    FloodEvents { interarrival: u32 },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub struct FaultWindow {
+    // Name of the task in the task set
+    pub task: &'static str,
+    // Job ids, both included
+    pub first_job: u32,
+    pub last_job: u32,
+    pub fault: Fault,
+}
+
+impl FaultWindow {
+    fn covers(&self, task: &str, job: u32) -> bool {
+        self.task == task && (self.first_job..=self.last_job).contains(&job)
+    }
+}
+
+// Runtime configuration of the injected faults
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FaultInjector {
+    windows: [Option<FaultWindow>; MAX_FAULTS],
+}
+
+impl FaultInjector {
+    pub const fn new() -> Self {
+        FaultInjector {
+            windows: [None; MAX_FAULTS],
+        }
+    }
+
+    // Returns false when all the slots are taken
+    pub fn add(&mut self, window: FaultWindow) -> bool {
+        match self.windows.iter_mut().find(|slot| slot.is_none()) {
+            Some(slot) => {
+                *slot = Some(window);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.windows = [None; MAX_FAULTS];
+    }
+
+    pub fn windows(&self) -> impl Iterator<Item = &FaultWindow> {
+        self.windows.iter().flatten()
+    }
+
+    fn active<'a>(&'a self, task: &'a str, job: u32) -> impl Iterator<Item = Fault> + 'a {
+        self.windows()
+            .filter(move |window| window.covers(task, job))
+            .map(|window| window.fault)
+    }
+
+    pub fn workload_factor(&self, task: &str, job: u32) -> u32 {
+        self.active(task, job)
+            .map(|fault| match fault {
+                Fault::InflateWorkload { factor } => factor,
+                _ => 1,
+            })
+            .fold(1, u32::saturating_mul)
+    }
+
+    // Milliseconds
+    pub fn release_delay(&self, task: &str, job: u32) -> u32 {
+        self.active(task, job)
+            .map(|fault| match fault {
+                Fault::ReleaseJitter { max } => jitter(job) % max.saturating_add(1),
+                _ => 0,
+            })
+            .fold(0, u32::saturating_add)
+    }
+
+    pub fn cancellation_suppressed(&self, task: &str, job: u32) -> bool {
+        self.active(task, job)
+            .any(|fault| fault == Fault::SuppressCancellation)
+    }
+
+    // Interarrival forced after the `interrupt`-th external interrupt of `task`
+    pub fn event_interarrival(&self, task: &str, interrupt: u32) -> Option<u32> {
+        self.active(task, interrupt).find_map(|fault| match fault {
+            Fault::FloodEvents { interarrival } => Some(interarrival),
+            _ => None,
+        })
+    }
+}
+
+impl Default for FaultInjector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Deterministic per job, so that a run can be reproduced
+fn jitter(job: u32) -> u32 {
+    let mut state = job.wrapping_mul(0x9E37_79B9) | 1;
+    state ^= state << 13;
+    state ^= state >> 17;
+    state ^= state << 5;
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        deadline::{DeadlineProtectedObject, MissPolicy},
+        deadline_timer::DeadlineTimer,
+        mock::{CountingNotify, MockMono},
+        time::Duration,
+    };
+
+    const TASK: &str = "Task";
+    const PERIOD: u32 = 100;
+    const DEADLINE: u32 = 50;
+    const EXECUTION: u32 = 10;
+
+    fn millis(millis: u32) -> Duration {
+        Duration::millis(1) * millis
+    }
+
+    // Periodic task driven through the deadline timer, returns the ids of the
+    // jobs whose deadline was missed
+    fn missed_jobs(injector: &FaultInjector, jobs: u32) -> Vec<u32> {
+        let mut mono = MockMono::new();
+        let mut timer: DeadlineTimer<CountingNotify, 1, 2> = DeadlineTimer::new(
+            [DeadlineProtectedObject::new(TASK, MissPolicy::LogOnly)],
+            CountingNotify::new(),
+        );
+        let [handle] = DeadlineTimer::<CountingNotify, 1, 2>::handles();
+        let mut missed = Vec::new();
+
+        for _ in 0..jobs {
+            let release = mono.now();
+            let job = timer.arm(handle, release + millis(DEADLINE));
+            let delay = injector.release_delay(TASK, job.id());
+            let execution = EXECUTION * injector.workload_factor(TASK, job.id());
+            let completion = mono.advance(millis(delay + execution));
+
+            // The timer service runs at a higher priority, the deadline expires
+            // before a later completion
+            if timer.next_expiry().is_some_and(|expiry| expiry <= completion) {
+                timer.expire(completion);
+                missed.push(job.id());
+            }
+            if !injector.cancellation_suppressed(TASK, job.id()) {
+                timer.cancel(job, completion);
+            }
+
+            let next_release = release + millis(PERIOD);
+            if completion < next_release {
+                mono.advance(next_release - completion);
+            }
+            // Deadlines left armed expire before the next release
+            while let Some(expiry) = timer.next_expiry().filter(|&expiry| expiry <= mono.now()) {
+                timer.expire(expiry);
+                missed.push(job.id());
+            }
+        }
+        assert_eq!(timer.object(handle).misses() as usize, missed.len());
+        missed
+    }
+
+    fn injector(first_job: u32, last_job: u32, fault: Fault) -> FaultInjector {
+        let mut injector = FaultInjector::new();
+        assert!(injector.add(FaultWindow { task: TASK, first_job, last_job, fault }));
+        injector
+    }
+
+    #[test]
+    fn no_fault_no_miss() {
+        assert!(missed_jobs(&FaultInjector::new(), 10).is_empty());
+    }
+
+    #[test]
+    fn inflated_workload_misses_inside_the_window_only() {
+        let injector = injector(3, 5, Fault::InflateWorkload { factor: 6 });
+        assert_eq!(missed_jobs(&injector, 10), vec![3, 4, 5]);
+    }
+
+    #[test]
+    fn suppressed_cancellation_is_a_miss() {
+        let injector = injector(7, 7, Fault::SuppressCancellation);
+        assert_eq!(missed_jobs(&injector, 10), vec![7]);
+    }
+
+    #[test]
+    fn jitter_within_the_slack_is_harmless() {
+        let injector = injector(1, 10, Fault::ReleaseJitter { max: DEADLINE - EXECUTION - 1 });
+        assert!(missed_jobs(&injector, 10).is_empty());
+    }
+
+    #[test]
+    fn faults_apply_to_their_task_only() {
+        let injector = injector(1, 1, Fault::InflateWorkload { factor: 3 });
+        assert_eq!(injector.workload_factor(TASK, 1), 3);
+        assert_eq!(injector.workload_factor("Other", 1), 1);
+        assert_eq!(injector.workload_factor(TASK, 2), 1);
+    }
+
+    #[test]
+    fn extreme_faults_saturate() {
+        let mut injector = injector(1, 1, Fault::InflateWorkload { factor: u32::MAX });
+        for fault in [Fault::InflateWorkload { factor: 2 }, Fault::ReleaseJitter { max: u32::MAX }] {
+            assert!(injector.add(FaultWindow { task: TASK, first_job: 1, last_job: 1, fault }));
+        }
+        assert_eq!(injector.workload_factor(TASK, 1), u32::MAX);
+        assert_eq!(injector.release_delay(TASK, 1), jitter(1));
+    }
+
+    #[test]
+    fn injector_has_bounded_slots() {
+        let mut injector = FaultInjector::new();
+        let window = FaultWindow { task: TASK, first_job: 1, last_job: 1, fault: Fault::SuppressCancellation };
+        for _ in 0..MAX_FAULTS {
+            assert!(injector.add(window));
+        }
+        assert!(!injector.add(window));
+        injector.clear();
+        assert_eq!(injector.windows().count(), 0);
+    }
+}
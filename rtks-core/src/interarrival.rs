@@ -0,0 +1,150 @@
+// Minimum interarrival enforcement for sporadic tasks, as a sporadic server:
+// an arrival sooner than the minimum interarrival after the previous release
+// is a violation, either deferred to the earliest legal release or rejected.
+use crate::{
+    task_set::TaskParameters,
+    time::{Duration, Instant},
+};
+
+// Arrivals are timestamped with the monotonic when the task gets to run, so
+// arrivals this much early are release jitter rather than violations
+pub const EARLINESS_TOLERANCE: Duration = Duration::millis(1);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum EarlyRelease {
+    // Release the job at the earliest instant allowed
+    Defer,
+    // Drop the release
+    Reject,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum Admission {
+    // Release instant of the job, later than its arrival if deferred
+    Release(Instant),
+    Rejected,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InterarrivalGuard {
+    min_interarrival: Duration,
+    policy: EarlyRelease,
+    last_release: Option<Instant>,
+    violations: u32,
+}
+
+impl InterarrivalGuard {
+    // Enforces the minimum interarrival of the task set
+    pub fn new(task: &TaskParameters, policy: EarlyRelease) -> Self {
+        InterarrivalGuard {
+            min_interarrival: Duration::millis(1) * task.period(),
+            policy,
+            last_release: None,
+            violations: 0,
+        }
+    }
+
+    pub fn admit(&mut self, arrival: Instant) -> Admission {
+        let Some(earliest) = self.last_release.map(|last| last + self.min_interarrival) else {
+            self.last_release = Some(arrival);
+            return Admission::Release(arrival);
+        };
+        if arrival + EARLINESS_TOLERANCE >= earliest {
+            // The window of the next release starts from the nominal earliest
+            // instant, tolerated early arrivals do not move it earlier
+            self.last_release = Some(arrival.max(earliest));
+            return Admission::Release(arrival);
+        }
+        self.violations += 1;
+        match self.policy {
+            EarlyRelease::Defer => {
+                self.last_release = Some(earliest);
+                Admission::Release(earliest)
+            }
+            EarlyRelease::Reject => Admission::Rejected,
+        }
+    }
+
+    pub fn violations(&self) -> u32 {
+        self.violations
+    }
+
+    pub fn policy(&self) -> EarlyRelease {
+        self.policy
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mock::MockMono, task_set::ON_CALL_PRODUCER};
+
+    fn millis(millis: u32) -> Duration {
+        Duration::millis(1) * millis
+    }
+
+    const MIN_INTERARRIVAL: u32 = ON_CALL_PRODUCER.period();
+
+    #[test]
+    fn arrivals_at_the_modelled_rate_are_released() {
+        let mut mono = MockMono::new();
+        let mut guard = InterarrivalGuard::new(&ON_CALL_PRODUCER, EarlyRelease::Reject);
+        for _ in 0..3 {
+            let arrival = mono.advance(millis(MIN_INTERARRIVAL));
+            assert_eq!(guard.admit(arrival), Admission::Release(arrival));
+        }
+        assert_eq!(guard.violations(), 0);
+    }
+
+    #[test]
+    fn burst_is_deferred_to_the_minimum_interarrival() {
+        let mut mono = MockMono::new();
+        let mut guard = InterarrivalGuard::new(&ON_CALL_PRODUCER, EarlyRelease::Defer);
+        let first = mono.now();
+        assert_eq!(guard.admit(first), Admission::Release(first));
+
+        // Both early, each one a minimum interarrival after the previous release
+        let arrival = mono.advance(millis(10));
+        assert_eq!(guard.admit(arrival), Admission::Release(first + millis(MIN_INTERARRIVAL)));
+        assert_eq!(guard.admit(arrival), Admission::Release(first + millis(2 * MIN_INTERARRIVAL)));
+        assert_eq!(guard.violations(), 2);
+    }
+
+    #[test]
+    fn early_arrival_is_rejected() {
+        let mut mono = MockMono::new();
+        let mut guard = InterarrivalGuard::new(&ON_CALL_PRODUCER, EarlyRelease::Reject);
+        let first = mono.now();
+        guard.admit(first);
+
+        assert_eq!(guard.admit(mono.advance(millis(MIN_INTERARRIVAL / 2))), Admission::Rejected);
+        // The rejected arrival does not count as a release
+        let arrival = mono.advance(millis(MIN_INTERARRIVAL / 2));
+        assert_eq!(guard.admit(arrival), Admission::Release(arrival));
+        assert_eq!(guard.violations(), 1);
+    }
+
+    #[test]
+    fn jitter_within_the_tolerance_is_tolerated() {
+        let mut mono = MockMono::new();
+        let mut guard = InterarrivalGuard::new(&ON_CALL_PRODUCER, EarlyRelease::Reject);
+        guard.admit(mono.now());
+
+        let arrival = mono.advance(millis(MIN_INTERARRIVAL) - EARLINESS_TOLERANCE);
+        assert_eq!(guard.admit(arrival), Admission::Release(arrival));
+        assert_eq!(guard.violations(), 0);
+    }
+
+    #[test]
+    fn tolerated_early_arrivals_do_not_drift_the_window() {
+        let mut mono = MockMono::new();
+        let mut guard = InterarrivalGuard::new(&ON_CALL_PRODUCER, EarlyRelease::Reject);
+        guard.admit(mono.now());
+
+        let early = millis(MIN_INTERARRIVAL) - EARLINESS_TOLERANCE;
+        guard.admit(mono.advance(early));
+        // Early by twice the tolerance from the nominal window
+        assert_eq!(guard.admit(mono.advance(early)), Admission::Rejected);
+        assert_eq!(guard.violations(), 1);
+    }
+}
@@ -0,0 +1,33 @@
+// Kernel-independent logic of the Ravenscar demonstrator.
+// Nothing in here depends on RTIC or on the target peripherals, so the crate
+// builds for `thumbv7em-none-eabihf` as well as for the host, where it is
+// unit tested with `cargo test-host`.
+#![cfg_attr(not(test), no_std)]
+
+pub mod activation_log;
+pub mod auxiliary;
+pub mod budget;
+pub mod config;
+pub mod console;
+pub mod deadline;
+pub mod deadline_timer;
+pub mod fault_injection;
+pub mod interarrival;
+pub mod mode;
+pub mod production_workload;
+pub mod request_buffer;
+pub mod stack;
+pub mod summary;
+pub mod task_set;
+pub mod time;
+pub mod timing;
+
+#[cfg(test)]
+mod mock;
+
+// Wake-up hook used by resources to release a task blocked on them
+// (e.g. the on call producer barrier), implemented by the firmware on top of
+// the RTIC synchronization primitives.
+pub trait Notify {
+    fn notify(&mut self);
+}
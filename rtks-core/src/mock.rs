@@ -0,0 +1,57 @@
+// Host replacements for the pieces normally provided by the firmware:
+// a defmt global logger that discards every frame, a manually driven clock
+// and a wake-up hook that counts its notifications.
+use crate::{
+    Notify,
+    time::{Duration, Instant},
+};
+
+#[defmt::global_logger]
+struct DiscardLogger;
+
+unsafe impl defmt::Logger for DiscardLogger {
+    fn acquire() {}
+    unsafe fn flush() {}
+    unsafe fn release() {}
+    unsafe fn write(_bytes: &[u8]) {}
+}
+
+defmt::timestamp!("{=u32}", 0);
+
+// Stand-in for `Mono`, time only moves when the test advances it
+pub struct MockMono {
+    now: Instant,
+}
+
+impl MockMono {
+    pub fn new() -> Self {
+        MockMono {
+            now: Instant::from_ticks(0),
+        }
+    }
+
+    pub fn now(&self) -> Instant {
+        self.now
+    }
+
+    pub fn advance(&mut self, delta: Duration) -> Instant {
+        self.now += delta;
+        self.now
+    }
+}
+
+// Stand-in for the RTIC signals behind the barriers and wakers, counts how
+// many times it has been notified
+pub struct CountingNotify(pub u32);
+
+impl CountingNotify {
+    pub fn new() -> Self {
+        CountingNotify(0)
+    }
+}
+
+impl Notify for CountingNotify {
+    fn notify(&mut self) {
+        self.0 += 1;
+    }
+}
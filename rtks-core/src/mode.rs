@@ -0,0 +1,261 @@
+// System-wide operating mode and mode change requests. The modes are
+// variants of the task set, a change requested by command, by a deadline miss
+// policy or by a schedule is carried out by the firmware's mode change
+// protocol once the jobs of the old mode are over.
+// Kept in atomics so that they can be read from any priority without a lock.
+use core::sync::atomic::{AtomicU8, AtomicU32, Ordering};
+
+use crate::task_set::{ACTIVATION_LOG_READER, ON_CALL_PRODUCER, REGULAR_PRODUCER, TaskParameters};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum SystemMode {
+    // Full task set
+    Nominal,
+    // Optional work (the on call producer releases) is shed
+    Degraded,
+    // Doubled workloads, at half the rate of the regular producer
+    HighLoad,
+}
+
+// Task set of a mode, relative to the nominal one
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub struct ModeParameters {
+    // The regular producer releases the on call producer
+    pub on_call_producer: bool,
+    // Multiplies the workloads, and the WCETs they are budgeted with
+    pub load_factor: u32,
+    // Multiplies the period and the deadline of the regular producer
+    pub period_factor: u32,
+}
+
+impl SystemMode {
+    pub const ALL: [SystemMode; 3] = [SystemMode::Nominal, SystemMode::Degraded, SystemMode::HighLoad];
+
+    pub const fn parameters(self) -> ModeParameters {
+        match self {
+            SystemMode::Nominal => ModeParameters {
+                on_call_producer: true,
+                load_factor: 1,
+                period_factor: 1,
+            },
+            SystemMode::Degraded => ModeParameters {
+                on_call_producer: false,
+                load_factor: 1,
+                period_factor: 1,
+            },
+            SystemMode::HighLoad => ModeParameters {
+                on_call_producer: true,
+                load_factor: 2,
+                period_factor: 2,
+            },
+        }
+    }
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            SystemMode::Nominal => "nominal",
+            SystemMode::Degraded => "degraded",
+            SystemMode::HighLoad => "high-load",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<SystemMode> {
+        SystemMode::ALL.into_iter().find(|mode| mode.name() == name)
+    }
+}
+
+impl ModeParameters {
+    fn has_workload(task: &TaskParameters) -> bool {
+        [REGULAR_PRODUCER, ON_CALL_PRODUCER, ACTIVATION_LOG_READER]
+            .iter()
+            .any(|other| other.name == task.name)
+    }
+
+    // Whether `task` is released in the mode
+    pub fn releases(&self, task: &TaskParameters) -> bool {
+        self.on_call_producer || task.name != ON_CALL_PRODUCER.name
+    }
+
+    // Period (or minimum interarrival) and relative deadline of `task` in the mode
+    pub fn period(&self, task: &TaskParameters) -> u32 {
+        if task.name == REGULAR_PRODUCER.name {
+            task.period() * self.period_factor
+        } else {
+            task.period()
+        }
+    }
+
+    pub fn deadline(&self, task: &TaskParameters) -> u32 {
+        if task.name == REGULAR_PRODUCER.name {
+            task.deadline * self.period_factor
+        } else {
+            task.deadline
+        }
+    }
+
+    pub fn wcet(&self, task: &TaskParameters) -> u32 {
+        if Self::has_workload(task) {
+            task.wcet * self.load_factor
+        } else {
+            task.wcet
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum ModeChangeTrigger {
+    Command,
+    DeadlineMiss,
+    Schedule,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub struct ModeChangeRequest {
+    pub mode: SystemMode,
+    pub trigger: ModeChangeTrigger,
+}
+
+impl ModeChangeRequest {
+    const NONE: u8 = u8::MAX;
+
+    const fn encode(self) -> u8 {
+        self.mode as u8 | (self.trigger as u8) << 4
+    }
+
+    fn decode(value: u8) -> Option<Self> {
+        let mode = *SystemMode::ALL.get((value & 0xF) as usize)?;
+        let trigger = match value >> 4 {
+            0 => ModeChangeTrigger::Command,
+            1 => ModeChangeTrigger::DeadlineMiss,
+            2 => ModeChangeTrigger::Schedule,
+            _ => return None,
+        };
+        Some(ModeChangeRequest { mode, trigger })
+    }
+}
+
+static MODE: AtomicU8 = AtomicU8::new(SystemMode::Nominal as u8);
+static REQUEST: AtomicU8 = AtomicU8::new(ModeChangeRequest::NONE);
+static CHANGES: AtomicU32 = AtomicU32::new(0);
+
+pub fn current() -> SystemMode {
+    SystemMode::ALL[MODE.load(Ordering::Relaxed) as usize]
+}
+
+// A later request replaces the pending one
+pub fn request(mode: SystemMode, trigger: ModeChangeTrigger) {
+    REQUEST.store(ModeChangeRequest { mode, trigger }.encode(), Ordering::Relaxed);
+}
+
+pub fn pending() -> Option<ModeChangeRequest> {
+    ModeChangeRequest::decode(REQUEST.load(Ordering::Relaxed))
+}
+
+// Ends the mode change: the requested mode becomes the current one, unless the
+// request was replaced in the meantime
+pub fn complete(request: ModeChangeRequest) -> bool {
+    let completed = REQUEST
+        .compare_exchange(request.encode(), ModeChangeRequest::NONE, Ordering::Relaxed, Ordering::Relaxed)
+        .is_ok();
+    if completed {
+        MODE.store(request.mode as u8, Ordering::Relaxed);
+        CHANGES.fetch_add(1, Ordering::Relaxed);
+    }
+    completed
+}
+
+// Mode changes completed since boot
+pub fn changes() -> u32 {
+    CHANGES.load(Ordering::Relaxed)
+}
+
+// Activations of a sporadic task by the regular producer, numbered from 1.
+// A mode change waits until the task has served all of them
+pub struct Activations {
+    issued: AtomicU32,
+    served: AtomicU32,
+}
+
+impl Activations {
+    pub const fn new() -> Self {
+        Activations {
+            issued: AtomicU32::new(0),
+            served: AtomicU32::new(0),
+        }
+    }
+
+    // Sequence number of the new activation
+    pub fn issue(&self) -> u32 {
+        self.issued.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    // The job that served activation `sequence` is over, together with the
+    // earlier activations it absorbed
+    pub fn served(&self, sequence: u32) {
+        self.served.fetch_max(sequence, Ordering::Relaxed);
+    }
+
+    // The job that served the oldest pending activation is over, for tasks
+    // that serve their activations one by one
+    pub fn served_next(&self) {
+        self.served.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn pending(&self) -> bool {
+        self.served.load(Ordering::Relaxed) != self.issued.load(Ordering::Relaxed)
+    }
+}
+
+impl Default for Activations {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task_set::EXTERNAL_EVENT_SERVER;
+
+    #[test]
+    fn high_load_doubles_the_workloads_at_half_the_rate() {
+        let parameters = SystemMode::HighLoad.parameters();
+        assert_eq!(parameters.wcet(&REGULAR_PRODUCER), 2 * REGULAR_PRODUCER.wcet);
+        assert_eq!(parameters.period(&REGULAR_PRODUCER), 2 * REGULAR_PRODUCER.period());
+        assert_eq!(parameters.wcet(&EXTERNAL_EVENT_SERVER), EXTERNAL_EVENT_SERVER.wcet);
+        assert_eq!(parameters.period(&ON_CALL_PRODUCER), ON_CALL_PRODUCER.period());
+        assert!(!SystemMode::Degraded.parameters().releases(&ON_CALL_PRODUCER));
+        assert_eq!(SystemMode::from_name("high-load"), Some(SystemMode::HighLoad));
+    }
+
+    #[test]
+    fn request_is_completed_unless_replaced() {
+        assert_eq!(pending(), None);
+        request(SystemMode::HighLoad, ModeChangeTrigger::Command);
+        let replaced = pending().unwrap();
+        request(SystemMode::Degraded, ModeChangeTrigger::DeadlineMiss);
+
+        assert!(!complete(replaced));
+        assert_eq!(current(), SystemMode::Nominal);
+        let request = pending().unwrap();
+        assert_eq!(request.trigger, ModeChangeTrigger::DeadlineMiss);
+        assert!(complete(request));
+        assert_eq!((current(), pending(), changes()), (SystemMode::Degraded, None, 1));
+    }
+
+    #[test]
+    fn activations_absorbed_by_a_job_are_served_with_it() {
+        let activations = Activations::new();
+        let first = activations.issue();
+        let second = activations.issue();
+        activations.served(first);
+        assert!(activations.pending());
+        activations.served(second);
+        activations.served(first);
+        assert!(!activations.pending());
+
+        activations.issue();
+        activations.served_next();
+        assert!(!activations.pending());
+    }
+}
@@ -0,0 +1,331 @@
+use core::{
+    error::Error,
+    fmt::Display,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use libm::{cosf, expf, fabsf, logf, sinf, sqrtf};
+
+use crate::timing::{CYCLES_PER_MICROSECOND, CYCLES_PER_MILLISECOND};
+
+// Type aliases for easy configuration
+type WhetFloat = f32;
+type WhetInt = i32;
+
+// Constants from the original Algol Whetstone program
+const T: WhetFloat = 0.499975;
+const T1: WhetFloat = 0.50025;
+const T2: WhetFloat = 2.0;
+const N8: usize = 10; // Loop iteration count for module 8
+const N9: usize = 7; // Loop iteration count for module 9
+const VALUE: WhetFloat = 0.941377; // Value calculated in main loop
+const TOLERANCE: WhetFloat = 0.00001; // Determined by interval arithmetic
+
+// Kilo-whetstones run at boot to measure the throughput, in rounds until at
+// least CALIBRATION_CYCLES have elapsed, so that a cycle count derived from a
+// coarse clock is still accurate to a few percent
+const CALIBRATION_KILO_WHETS: u32 = 100;
+const CALIBRATION_CYCLES: u32 = 20 * CYCLES_PER_MILLISECOND;
+const CALIBRATION_MAX_ROUNDS: u32 = 200;
+// Throughput assumed before calibration, or when the cycle counter does not run
+const DEFAULT_CYCLES_PER_KILO_WHET: u32 = 2_000;
+// Granularity of the abort points of an abortable workload (microseconds)
+pub const ABORT_SLICE: u32 = 500;
+
+static CYCLES_PER_KILO_WHET: AtomicU32 = AtomicU32::new(DEFAULT_CYCLES_PER_KILO_WHET);
+
+// Custom error type for workload failure
+#[derive(Debug, defmt::Format)]
+pub struct WorkloadFailure {
+    actual: WhetFloat,
+    expected: WhetFloat,
+}
+
+impl Display for WorkloadFailure {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(
+            f,
+            "Whetstone computation failed, actual = {}, expected = {}",
+            self.actual, self.expected
+        )
+    }
+}
+
+impl Error for WorkloadFailure {}
+
+pub fn small_whetstone(kilo_whets: u32) -> Result<(), WorkloadFailure> {
+    let mut ij: WhetInt = 1;
+    let mut ik: WhetInt = 2;
+    let mut il: WhetInt = 3;
+
+    let y: WhetFloat = 1.0; // Constant within loop
+    let mut z: WhetFloat;
+    let mut sum: WhetFloat = 0.0; // Accumulates value of Z
+    let mut e1: [WhetFloat; N9] = [0.0; N9]; // Array indexed 0..N9-1 (equivalent to 1..N9 in Ada)
+
+    // Helper function to clear array
+    fn clear_array(arr: &mut [WhetFloat; N9]) {
+        for elem in arr.iter_mut() {
+            *elem = 0.0;
+        }
+    }
+
+    // P0 procedure - handles array bounds checking like Ada version
+    fn p0(ij: WhetInt, ik: WhetInt, il: WhetInt, i: usize, e1: &mut [WhetFloat; N9]) {
+        // Convert to 0-based indexing and check bounds
+        let ij_idx = (ij - 1) as usize;
+        let ik_idx = (ik - 1) as usize;
+        let il_idx = (il - 1) as usize;
+
+        if ij_idx < N9 && ik_idx < N9 && il_idx < N9 && i < N9 {
+            e1[ij_idx] = e1[ik_idx];
+            e1[ik_idx] = e1[il_idx];
+            e1[i] = e1[ij_idx];
+        }
+    }
+
+    // P3 procedure
+    fn p3(x: WhetFloat, y: WhetFloat, z: &mut WhetFloat) {
+        let xtemp: WhetFloat = T * (*z + x);
+        let ytemp: WhetFloat = T * (xtemp + y);
+        *z = (xtemp + ytemp) / T2;
+    }
+
+    // Main benchmark loop
+    for _outer_loop_var in 1..=kilo_whets {
+        clear_array(&mut e1);
+
+        // Module 6: Integer arithmetic
+        defmt::trace!(
+            "Iteration: {}, ij = {}, ik = {}, il = {}",
+            _outer_loop_var,
+            ij,
+            ik,
+            il
+        );
+        ij = (ik - ij) * (il - ik);
+        ik = il - (ik - ij);
+        il = (il - ik) * (ik + il);
+        defmt::trace!(
+            "Iteration: {}, ij = {}, ik = {}, il = {}",
+            _outer_loop_var,
+            ij,
+            ik,
+            il
+        );
+
+        // Convert to 0-based indexing and handle bounds
+        let il_idx = (il - 2) as usize;
+        e1[il_idx] = (ij + ik + il) as WhetFloat;
+
+        let ik_idx = (ik - 2) as usize;
+        if ik_idx < N9 {
+            e1[ik_idx] = sinf(il as WhetFloat) as WhetFloat;
+        } else {
+            e1[N9 - 1] = sinf(il as WhetFloat) as WhetFloat;
+        }
+
+        // Module 8: Procedure calls
+        z = e1[3]; // E1(4) in Ada is E1[3] in 0-based indexing
+        for inner_loop_var in 1..=N8 {
+            p3(y * inner_loop_var as WhetFloat, y + z, &mut z);
+        }
+
+        // Second version of Module 6
+        ij = il - (il - 3) * ik;
+        il = (il - ik) * (ik - ij);
+        ik = (il - ik) * ik;
+        defmt::trace!(
+            "Iteration: {}, ij = {}, ik = {}, il = {}",
+            _outer_loop_var,
+            ij,
+            ik,
+            il
+        );
+
+        let il_idx = (il - 2) as usize;
+        if il_idx < N9 {
+            e1[il_idx] = (ij + ik + il) as WhetFloat;
+        } else {
+            e1[N9 - 1] = (ij + ik + il) as WhetFloat;
+        }
+
+        let ik_idx = ik as usize; // ik + 1 converted to 0-based
+        if ik_idx < N9 {
+            e1[ik_idx] = fabsf(cosf(z)) as WhetFloat;
+        } else {
+            e1[N9 - 1] = fabsf(cosf(z)) as WhetFloat;
+        }
+
+        // Module 9: Array references
+        // Using while loop like the Ada version to allow I to be used in P0
+        for i in 0..N9 {
+            // equivalent to I <= N9 in Ada
+            p0(ij, ik, il, i, &mut e1);
+        }
+
+        // Module 11: Standard mathematical functions
+        if e1[N9 - 1] > 0.0 {
+            // E1(N9) in Ada is E1[N9-1] in 0-based indexing
+            z = sqrtf(expf(logf(e1[N9 - 1]) / T1)) as WhetFloat;
+        } else {
+            z = sqrtf(expf(logf(1.1) / T1)) as WhetFloat;
+        }
+
+        sum += z;
+
+        // Check the current value of the loop computation
+        defmt::debug!("|z - value| = {}", (z - VALUE).abs());
+        if (z - VALUE).abs() > TOLERANCE {
+            sum *= 2.0; // Forces error at end
+            ij += 1; // Prevents optimization
+        }
+    }
+
+    // Self-validation check
+    let actual = sum / kilo_whets as WhetFloat - VALUE;
+    let expected = kilo_whets as WhetFloat;
+    if actual.abs() > TOLERANCE * expected {
+        return Err(WorkloadFailure { actual, expected });
+    }
+
+    Ok(())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum Calibration {
+    // Cycles taken by one kilo-whetstone
+    Measured(u32),
+    // The cycle count did not advance, the throughput is still the default one
+    Unmeasured(u32),
+}
+
+// Measure the whetstone throughput on the current clock, `cycle_count` reads
+// the core cycle counter
+pub fn calibrate(cycle_count: impl Fn() -> u32) -> Result<Calibration, WorkloadFailure> {
+    let start = cycle_count();
+    let mut kilo_whets = 0;
+    let mut cycles = 0;
+    for _ in 0..CALIBRATION_MAX_ROUNDS {
+        small_whetstone(CALIBRATION_KILO_WHETS)?;
+        kilo_whets += CALIBRATION_KILO_WHETS;
+        cycles = cycle_count().wrapping_sub(start);
+        if cycles >= CALIBRATION_CYCLES {
+            break;
+        }
+    }
+
+    match cycles_per_kilo_whet(cycles, kilo_whets) {
+        Some(throughput) => {
+            CYCLES_PER_KILO_WHET.store(throughput, Ordering::Relaxed);
+            Ok(Calibration::Measured(throughput))
+        }
+        None => Ok(Calibration::Unmeasured(CYCLES_PER_KILO_WHET.load(Ordering::Relaxed))),
+    }
+}
+
+// Kilo-whetstones needed to execute for `micros` microseconds
+pub fn kilo_whets_for(micros: u32) -> u32 {
+    kilo_whets_in(micros, CYCLES_PER_KILO_WHET.load(Ordering::Relaxed))
+}
+
+// Run the whetstone workload for (at least) `micros` microseconds of execution time
+pub fn execute_for(micros: u32) -> Result<(), WorkloadFailure> {
+    match kilo_whets_for(micros) {
+        0 => Ok(()),
+        kilo_whets => small_whetstone(kilo_whets),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum WorkloadOutcome {
+    Completed,
+    Aborted,
+}
+
+// Same as `execute_for`, but run in slices of ABORT_SLICE microseconds and give
+// up as soon as `abort` returns true between two slices
+pub fn execute_abortable_for(
+    micros: u32,
+    mut abort: impl FnMut() -> bool,
+) -> Result<WorkloadOutcome, WorkloadFailure> {
+    let mut remaining = micros;
+    while remaining > 0 {
+        if abort() {
+            return Ok(WorkloadOutcome::Aborted);
+        }
+        let slice = remaining.min(ABORT_SLICE);
+        execute_for(slice)?;
+        remaining -= slice;
+    }
+    Ok(WorkloadOutcome::Completed)
+}
+
+fn cycles_per_kilo_whet(calibration_cycles: u32, kilo_whets: u32) -> Option<u32> {
+    match calibration_cycles / kilo_whets {
+        0 => None,
+        throughput => Some(throughput),
+    }
+}
+
+fn kilo_whets_in(micros: u32, cycles_per_kilo_whet: u32) -> u32 {
+    let cycles = micros as u64 * CYCLES_PER_MICROSECOND as u64;
+    cycles
+        .div_ceil(cycles_per_kilo_whet as u64)
+        .min(u32::MAX as u64) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn calibration_divides_over_calibration_run() {
+        assert_eq!(cycles_per_kilo_whet(CALIBRATION_KILO_WHETS * 1_500, CALIBRATION_KILO_WHETS), Some(1_500));
+        // A stopped cycle counter gives no measurement
+        assert_eq!(cycles_per_kilo_whet(0, CALIBRATION_KILO_WHETS), None);
+    }
+
+    #[test]
+    fn calibration_runs_until_enough_cycles_elapsed() {
+        // Advances at the default throughput, which the other tests rely on
+        let cycles = core::cell::Cell::new(0u32);
+        let cycle_count = || cycles.replace(cycles.get() + DEFAULT_CYCLES_PER_KILO_WHET * CALIBRATION_KILO_WHETS);
+        assert_eq!(calibrate(cycle_count).unwrap(), Calibration::Measured(DEFAULT_CYCLES_PER_KILO_WHET));
+        assert!(cycles.get() >= CALIBRATION_CYCLES);
+    }
+
+    #[test]
+    fn budget_is_rounded_up_to_whole_kilo_whets() {
+        // 1 us is CYCLES_PER_MICROSECOND cycles
+        assert_eq!(kilo_whets_in(10, CYCLES_PER_MICROSECOND * 5), 2);
+        assert_eq!(kilo_whets_in(11, CYCLES_PER_MICROSECOND * 5), 3);
+        assert_eq!(kilo_whets_in(0, CYCLES_PER_MICROSECOND), 0);
+    }
+
+    #[test]
+    fn zero_budget_does_nothing() {
+        assert!(execute_for(0).is_ok());
+    }
+
+    #[test]
+    fn abortable_workload_stops_at_next_slice() {
+        let mut checks = 0;
+        let outcome = execute_abortable_for(4 * ABORT_SLICE, || {
+            checks += 1;
+            checks == 3
+        });
+        assert!(matches!(outcome, Ok(WorkloadOutcome::Aborted)));
+        assert_eq!(checks, 3);
+
+        let outcome = execute_abortable_for(ABORT_SLICE + 1, || false);
+        assert!(matches!(outcome, Ok(WorkloadOutcome::Completed)));
+    }
+
+    #[test]
+    fn small_whetstone_self_validates() {
+        for kilo_whets in [1, 10, 278, 756] {
+            assert!(small_whetstone(kilo_whets).is_ok(), "kilo_whets = {kilo_whets}");
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use core::ops::AddAssign;
+
+use crate::Notify;
+
+const REQUEST_BUFFER_RANGE: usize = 5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct RequestBufferIndex(usize);
+
+impl RequestBufferIndex {
+    fn first() -> Self {
+        RequestBufferIndex(0)
+    }
+}
+
+// Override += operator
+impl AddAssign<usize> for RequestBufferIndex {
+    fn add_assign(&mut self, rhs: usize) {
+        self.0 = (self.0 + rhs) % REQUEST_BUFFER_RANGE;
+    }
+}
+
+// Activation request of the on call producer
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub struct Request {
+    pub activation_parameter: u32,
+    // Cycle counter value at the deposit, the release time of the job
+    pub arrival: u32,
+}
+
+impl Request {
+    const EMPTY: Request = Request {
+        activation_parameter: 0,
+        arrival: 0,
+    };
+}
+
+// Bounded FIFO circular buffer of on call producer activation requests.
+// It acts as a protected entry with barrier `current_size > 0`: the barrier is
+// notified on every deposit, but the notifications may be coalesced, so the
+// consumer extracts until the buffer is empty before waiting again.
+pub struct RequestBuffer<B: Notify> {
+    my_request_buffer: [Request; REQUEST_BUFFER_RANGE],
+    insert_index: RequestBufferIndex,
+    extract_index: RequestBufferIndex,
+    current_size: usize,
+    rejected: u32,
+    barrier: B,
+}
+
+impl<B: Notify> RequestBuffer<B> {
+    pub fn new(barrier: B) -> Self {
+        RequestBuffer {
+            my_request_buffer: [Request::EMPTY; REQUEST_BUFFER_RANGE],
+            insert_index: RequestBufferIndex::first(),
+            extract_index: RequestBufferIndex::first(),
+            current_size: 0,
+            rejected: 0,
+            barrier,
+        }
+    }
+
+    pub fn deposit(&mut self, request: Request) -> bool {
+        if self.current_size < REQUEST_BUFFER_RANGE {
+            self.my_request_buffer[self.insert_index.0] = request;
+            self.insert_index += 1;
+            self.current_size += 1;
+            self.barrier.notify();
+            true
+        } else {
+            self.rejected += 1;
+            false
+        }
+    }
+
+    // None while the entry barrier is closed
+    pub fn extract(&mut self) -> Option<Request> {
+        if self.current_size == 0 {
+            return None;
+        }
+        let request = self.my_request_buffer[self.extract_index.0];
+        self.extract_index += 1;
+        self.current_size -= 1;
+        Some(request)
+    }
+
+    // Number of pending requests
+    pub fn current_size(&self) -> usize {
+        self.current_size
+    }
+
+    pub fn capacity(&self) -> usize {
+        REQUEST_BUFFER_RANGE
+    }
+
+    // Number of deposits refused because the buffer was full
+    pub fn rejected(&self) -> u32 {
+        self.rejected
+    }
+
+    pub fn statistics(&self) -> RequestBufferStatistics {
+        RequestBufferStatistics {
+            current_size: self.current_size,
+            capacity: REQUEST_BUFFER_RANGE,
+            rejected: self.rejected,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub struct RequestBufferStatistics {
+    pub current_size: usize,
+    pub capacity: usize,
+    pub rejected: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::CountingNotify;
+
+    // The barrier counts how many times it has been opened
+    fn request_buffer() -> RequestBuffer<CountingNotify> {
+        RequestBuffer::new(CountingNotify::new())
+    }
+
+    fn request(workload: u32) -> Request {
+        Request {
+            activation_parameter: workload,
+            arrival: workload,
+        }
+    }
+
+    fn workload(request: Option<Request>) -> Option<u32> {
+        request.map(|request| request.activation_parameter)
+    }
+
+    #[test]
+    fn extract_from_empty_buffer_is_none() {
+        let mut buffer = request_buffer();
+        assert_eq!(buffer.extract(), None);
+        assert_eq!(buffer.current_size(), 0);
+    }
+
+    #[test]
+    fn requests_are_extracted_in_fifo_order() {
+        let mut buffer = request_buffer();
+        for workload in [10, 20, 30] {
+            assert!(buffer.deposit(request(workload)));
+        }
+
+        assert_eq!(workload(buffer.extract()), Some(10));
+        assert_eq!(workload(buffer.extract()), Some(20));
+        assert_eq!(workload(buffer.extract()), Some(30));
+        assert_eq!(buffer.extract(), None);
+        assert_eq!(buffer.barrier.0, 3);
+    }
+
+    // The RTIC signal the firmware uses as barrier: it holds at most one
+    // pending wake-up, however many times it is written
+    struct SignalBarrier(bool);
+
+    impl Notify for SignalBarrier {
+        fn notify(&mut self) {
+            self.0 = true;
+        }
+    }
+
+    impl SignalBarrier {
+        fn wait(&mut self) -> bool {
+            core::mem::replace(&mut self.0, false)
+        }
+    }
+
+    #[test]
+    fn back_to_back_deposits_are_all_extracted() {
+        let mut buffer = RequestBuffer::new(SignalBarrier(false));
+        for workload in [10, 20, 30] {
+            assert!(buffer.deposit(request(workload)));
+        }
+
+        // Entry loop of the on call producer: extract until the barrier
+        // closes, wait only then
+        let mut served = Vec::new();
+        let mut wake_ups = 0;
+        while buffer.barrier.wait() {
+            wake_ups += 1;
+            while let Some(request) = buffer.extract() {
+                served.push(request);
+            }
+        }
+        assert_eq!(wake_ups, 1);
+        assert_eq!(served, [request(10), request(20), request(30)]);
+        assert_eq!(buffer.current_size(), 0);
+    }
+
+    #[test]
+    fn whole_capacity_is_usable_and_overflow_is_rejected() {
+        let mut buffer = request_buffer();
+        for workload in 0..REQUEST_BUFFER_RANGE as u32 {
+            assert!(buffer.deposit(request(workload)));
+        }
+        assert_eq!(buffer.current_size(), buffer.capacity());
+
+        assert!(!buffer.deposit(request(99)));
+        assert!(!buffer.deposit(request(99)));
+        assert_eq!(buffer.rejected(), 2);
+        assert_eq!(buffer.barrier.0, REQUEST_BUFFER_RANGE as u32);
+    }
+
+    #[test]
+    fn indices_wrap_around() {
+        let mut buffer = request_buffer();
+        for round in 0..3 * REQUEST_BUFFER_RANGE as u32 {
+            assert!(buffer.deposit(request(round)));
+            assert!(buffer.deposit(request(round + 100)));
+            assert_eq!(workload(buffer.extract()), Some(round));
+            assert_eq!(workload(buffer.extract()), Some(round + 100));
+        }
+        assert_eq!(buffer.current_size(), 0);
+    }
+}
@@ -0,0 +1,120 @@
+// Stack usage measurement by stack painting. The free part of the stack is
+// filled with PAINT at boot, the high-water mark is the deepest word that no
+// longer holds it. RTIC runs every priority level on the one main stack, so
+// the usage of a level can only be estimated, from the stack pointer sampled
+// while its tasks run: the estimate includes the frames of the lower levels
+// they preempted, and misses the frames deeper than the sampling points.
+use core::sync::atomic::{AtomicU32, Ordering};
+
+pub const PAINT: u32 = 0xCCCC_CCCC;
+// Priority levels of the NVIC of the STM32F4
+pub const LEVELS: usize = 16;
+
+// Number of words still painted at the far end of the stack, `words` going
+// from the lowest address up
+pub fn painted(words: impl IntoIterator<Item = u32>) -> u32 {
+    words.into_iter().take_while(|&word| word == PAINT).count() as u32
+}
+
+// Deepest stack sampled at each priority level, in bytes
+pub struct LevelDepths {
+    deepest: [AtomicU32; LEVELS],
+}
+
+impl LevelDepths {
+    pub const fn new() -> Self {
+        LevelDepths { deepest: [const { AtomicU32::new(0) }; LEVELS] }
+    }
+
+    pub fn record(&self, priority: u8, depth: u32) {
+        self.deepest[priority as usize].fetch_max(depth, Ordering::Relaxed);
+    }
+
+    pub fn deepest(&self) -> [u32; LEVELS] {
+        core::array::from_fn(|priority| self.deepest[priority].load(Ordering::Relaxed))
+    }
+}
+
+impl Default for LevelDepths {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StackUsage {
+    // Bytes
+    pub size: u32,
+    pub high_water_mark: u32,
+    // Deepest sample of each priority level, 0 for the levels never sampled
+    pub levels: [u32; LEVELS],
+}
+
+impl StackUsage {
+    pub fn percent_used(&self) -> u32 {
+        (self.high_water_mark as u64 * 100 / (self.size as u64).max(1)) as u32
+    }
+
+    // Less than a quarter of the stack left
+    pub fn near_overflow(&self) -> bool {
+        self.high_water_mark > self.size / 4 * 3
+    }
+
+    pub fn log_high_water_mark(&self) {
+        if self.near_overflow() {
+            defmt::warn!(
+                "Stack: high-water mark {} of {} bytes ({}%), near overflow",
+                self.high_water_mark,
+                self.size,
+                self.percent_used()
+            );
+        } else {
+            defmt::info!(
+                "Stack: high-water mark {} of {} bytes ({}%)",
+                self.high_water_mark,
+                self.size,
+                self.percent_used()
+            );
+        }
+    }
+
+    pub fn log(&self) {
+        self.log_high_water_mark();
+        for (priority, &depth) in self.levels.iter().enumerate() {
+            if depth > 0 {
+                defmt::info!("Stack: priority {} sampled {} bytes deep", priority, depth);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn painted_words_end_at_the_first_overwritten_one() {
+        assert_eq!(painted([PAINT, PAINT, 0, PAINT, 7]), 2);
+        assert_eq!(painted([PAINT; 4]), 4);
+        assert_eq!(painted([0, PAINT]), 0);
+    }
+
+    #[test]
+    fn levels_keep_their_deepest_sample() {
+        let depths = LevelDepths::new();
+        depths.record(5, 300);
+        depths.record(5, 200);
+        depths.record(11, 450);
+
+        let deepest = depths.deepest();
+        assert_eq!((deepest[5], deepest[11], deepest[7]), (300, 450, 0));
+    }
+
+    #[test]
+    fn three_quarters_used_is_near_overflow() {
+        let usage = |high_water_mark| StackUsage { size: 4096, high_water_mark, levels: [0; LEVELS] };
+        assert_eq!(usage(1024).percent_used(), 25);
+        assert!(!usage(3072).near_overflow());
+        assert!(usage(3076).near_overflow());
+    }
+}
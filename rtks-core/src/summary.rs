@@ -0,0 +1,125 @@
+use crate::{
+    deadline::DeadlineStatistics,
+    request_buffer::RequestBufferStatistics,
+    stack::StackUsage,
+    time::Instant,
+};
+
+// State of the system collected by the supervisor at the end of a bounded run,
+// and by the console on request
+pub struct RunSummary<const TASKS: usize> {
+    pub duration: u32,
+    pub deadlines: [DeadlineStatistics; TASKS],
+    pub activation_log: (u32, Option<Instant>),
+    pub request_buffer: RequestBufferStatistics,
+    pub stack: StackUsage,
+}
+
+impl<const TASKS: usize> RunSummary<TASKS> {
+    pub fn misses(&self) -> u32 {
+        self.deadlines.iter().map(|deadline| deadline.misses).sum()
+    }
+
+    // Every deadline of the task set is hard, a single miss fails the run
+    pub fn passed(&self) -> bool {
+        self.misses() == 0
+    }
+
+    pub fn log(&self) {
+        defmt::info!("Run summary: duration = {} ms", self.duration);
+        for deadline in self.deadlines.iter() {
+            defmt::info!(
+                "Task '{}': activations = {}, misses = {}, skipped = {}",
+                deadline.name,
+                deadline.activations,
+                deadline.misses,
+                deadline.skipped
+            );
+            defmt::info!(
+                "Task '{}': response time {}, execution time {}",
+                deadline.name,
+                deadline.response_time,
+                deadline.execution_time
+            );
+            defmt::info!("Task '{}': release jitter {}", deadline.name, deadline.release_jitter);
+            if deadline.interarrival_violations > 0 {
+                defmt::warn!(
+                    "Task '{}': {} releases earlier than the minimum interarrival",
+                    deadline.name,
+                    deadline.interarrival_violations
+                );
+            }
+            if deadline.misses > 0 {
+                defmt::warn!("Task '{}': {}", deadline.name, deadline.lateness);
+            }
+        }
+        defmt::info!(
+            "Activation log: activations = {}, last = {}",
+            self.activation_log.0,
+            self.activation_log.1
+        );
+        defmt::info!(
+            "Request buffer: pending = {}/{}, rejected = {}",
+            self.request_buffer.current_size,
+            self.request_buffer.capacity,
+            self.request_buffer.rejected
+        );
+        self.stack.log();
+        if self.passed() {
+            defmt::info!("Run result: PASS");
+        } else {
+            defmt::error!("Run result: FAIL, {} deadline misses", self.misses());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{deadline::LatenessStatistics, stack::LEVELS, timing::TimingStatistics};
+
+    fn summary(misses: [u32; 2]) -> RunSummary<2> {
+        RunSummary {
+            duration: 10_000,
+            deadlines: [
+                DeadlineStatistics {
+                    name: "A",
+                    activations: 10,
+                    misses: misses[0],
+                    skipped: 0,
+                    response_time: TimingStatistics::new(),
+                    execution_time: TimingStatistics::new(),
+                    release_jitter: TimingStatistics::new(),
+                    interarrival_violations: 0,
+                    lateness: LatenessStatistics::new(),
+                },
+                DeadlineStatistics {
+                    name: "B",
+                    activations: 3,
+                    misses: misses[1],
+                    skipped: 0,
+                    response_time: TimingStatistics::new(),
+                    execution_time: TimingStatistics::new(),
+                    release_jitter: TimingStatistics::new(),
+                    interarrival_violations: 0,
+                    lateness: LatenessStatistics::new(),
+                },
+            ],
+            activation_log: (0, None),
+            request_buffer: RequestBufferStatistics { current_size: 0, capacity: 5, rejected: 0 },
+            stack: StackUsage { size: 4096, high_water_mark: 1024, levels: [0; LEVELS] },
+        }
+    }
+
+    #[test]
+    fn run_without_misses_passes() {
+        assert!(summary([0, 0]).passed());
+    }
+
+    #[test]
+    fn any_miss_fails_the_run() {
+        let summary = summary([0, 2]);
+        assert_eq!(summary.misses(), 2);
+        assert!(!summary.passed());
+    }
+}
@@ -0,0 +1,226 @@
+// Single source of truth of the task set parameters, shared by the firmware
+// and by the host-side MAST model generator (`rtks-mast`) and response-time
+// analysis (`rtks-rta`).
+// Periods and deadlines are in milliseconds, execution times in microseconds. RTIC only accepts literal priorities in
+// `#[task(priority = ...)]`, `rtks-mast` checks that `src/main.rs` agrees with
+// the priorities below.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Activation {
+    Periodic { period: u32 },
+    Sporadic { min_interarrival: u32 },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TaskParameters {
+    // Name of the RTIC task and of the MAST scheduling server
+    pub name: &'static str,
+    // Prefix of the MAST operations and events of the task (e.g. `rp_job`)
+    pub mnemonic: &'static str,
+    pub priority: u8,
+    pub activation: Activation,
+    // Relative deadline
+    pub deadline: u32,
+    // Release offset (phase) from the system start epoch: the first release of
+    // a periodic task, the first instant a sporadic task accepts releases.
+    // All zero is the synchronous release of the critical instant
+    pub offset: u32,
+    // Worst-case execution time budget, refine it with the high-water marks
+    // reported at the end of a bounded run
+    pub wcet: u32,
+}
+
+impl TaskParameters {
+    // Period of a periodic task, minimum interarrival of a sporadic one
+    pub const fn period(&self) -> u32 {
+        match self.activation {
+            Activation::Periodic { period } => period,
+            Activation::Sporadic { min_interarrival } => min_interarrival,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ResourceUser {
+    pub priority: u8,
+    // Longest critical section of the user on the resource
+    pub critical_section: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SharedResource {
+    pub name: &'static str,
+    pub users: &'static [ResourceUser],
+}
+
+impl SharedResource {
+    // Stack Resource Policy ceiling, as computed by RTIC
+    pub const fn ceiling(&self) -> u8 {
+        let mut ceiling = 0;
+        let mut i = 0;
+        while i < self.users.len() {
+            if self.users[i].priority > ceiling {
+                ceiling = self.users[i].priority;
+            }
+            i += 1;
+        }
+        ceiling
+    }
+}
+
+pub const REGULAR_PRODUCER: TaskParameters = TaskParameters {
+    name: "regular_producer",
+    mnemonic: "rp",
+    priority: 7,
+    activation: Activation::Periodic { period: 1_000 },
+    deadline: 500,
+    offset: 0,
+    wcet: 10_000,
+};
+
+// Released by the regular producer every `moduli.due_activation` periods of
+// the system configuration
+pub const ON_CALL_PRODUCER: TaskParameters = TaskParameters {
+    name: "on_call_producer",
+    mnemonic: "ocp",
+    priority: 5,
+    activation: Activation::Sporadic { min_interarrival: 5_000 },
+    deadline: 800,
+    offset: 0,
+    wcet: 5_000,
+};
+
+// Released by the regular producer every `moduli.check_due_factor` periods of
+// the system configuration
+pub const ACTIVATION_LOG_READER: TaskParameters = TaskParameters {
+    name: "activation_log_reader",
+    mnemonic: "alr",
+    priority: 3,
+    activation: Activation::Sporadic { min_interarrival: 3_000 },
+    deadline: 1_000,
+    offset: 0,
+    wcet: 15_000,
+};
+
+// Released by the external interrupt
+pub const EXTERNAL_EVENT_SERVER: TaskParameters = TaskParameters {
+    name: "external_event_server",
+    mnemonic: "ees",
+    priority: 11,
+    activation: Activation::Sporadic { min_interarrival: 5_000 },
+    deadline: 100,
+    offset: 0,
+    wcet: 100,
+};
+
+pub const TASKS: [TaskParameters; 4] = [
+    REGULAR_PRODUCER,
+    ON_CALL_PRODUCER,
+    ACTIVATION_LOG_READER,
+    EXTERNAL_EVENT_SERVER,
+];
+
+// Priority of the deadline timer service and its execution time per expired
+// deadline, at most one per release of each task
+pub const DEADLINE_TIMER_PRIORITY: u8 = 12;
+pub const DEADLINE_TIMER_WCET: u32 = 20;
+// Priority and execution time of the hardware task raising the external events
+pub const EXTERNAL_INTERRUPT_PRIORITY: u8 = 13;
+pub const EXTERNAL_INTERRUPT_WCET: u32 = 10;
+// Serial console: the receive interrupt handles one character, the command
+// interpreter runs in the background below every task of the task set
+pub const CONSOLE_PRIORITY: u8 = 1;
+pub const CONSOLE_RX_PRIORITY: u8 = 14;
+pub const CONSOLE_RX_WCET: u32 = 5;
+pub const CONSOLE_BAUD_RATE: u32 = 115_200;
+// Shortest interarrival of the receive interrupts (microseconds), 10 bits per character
+pub const CONSOLE_CHARACTER_TIME: u32 = 10 * 1_000_000 / CONSOLE_BAUD_RATE;
+
+// Longest critical sections on the shared resources
+const REQUEST_BUFFER_CRITICAL_SECTION: u32 = 5;
+const ACTIVATION_LOG_CRITICAL_SECTION: u32 = 5;
+const DEADLINE_CRITICAL_SECTION: u32 = 10;
+const SYSTEM_CONFIG_CRITICAL_SECTION: u32 = 2;
+// The console copies the statistics of the whole resource
+const CONSOLE_CRITICAL_SECTION: u32 = 20;
+
+const fn user(task: &TaskParameters, critical_section: u32) -> ResourceUser {
+    ResourceUser {
+        priority: task.priority,
+        critical_section,
+    }
+}
+
+const DEADLINE_TIMER_SERVICE: ResourceUser = ResourceUser {
+    priority: DEADLINE_TIMER_PRIORITY,
+    critical_section: DEADLINE_CRITICAL_SECTION,
+};
+
+const CONSOLE: ResourceUser = ResourceUser {
+    priority: CONSOLE_PRIORITY,
+    critical_section: CONSOLE_CRITICAL_SECTION,
+};
+
+pub const REQUEST_BUFFER: SharedResource = SharedResource {
+    name: "request_buffer",
+    users: &[
+        user(&REGULAR_PRODUCER, REQUEST_BUFFER_CRITICAL_SECTION),
+        user(&ON_CALL_PRODUCER, REQUEST_BUFFER_CRITICAL_SECTION),
+        CONSOLE,
+    ],
+};
+
+pub const ACTIVATION_LOG: SharedResource = SharedResource {
+    name: "activation_log",
+    users: &[
+        user(&EXTERNAL_EVENT_SERVER, ACTIVATION_LOG_CRITICAL_SECTION),
+        user(&ACTIVATION_LOG_READER, ACTIVATION_LOG_CRITICAL_SECTION),
+        CONSOLE,
+    ],
+};
+
+// Deadline protected objects of every task, held by the deadline timer service
+pub const DEADLINE_TIMER: SharedResource = SharedResource {
+    name: "deadline_timer",
+    users: &[
+        user(&REGULAR_PRODUCER, DEADLINE_CRITICAL_SECTION),
+        user(&ON_CALL_PRODUCER, DEADLINE_CRITICAL_SECTION),
+        user(&ACTIVATION_LOG_READER, DEADLINE_CRITICAL_SECTION),
+        user(&EXTERNAL_EVENT_SERVER, DEADLINE_CRITICAL_SECTION),
+        DEADLINE_TIMER_SERVICE,
+        CONSOLE,
+    ],
+};
+
+// Copied by the tasks at their release, replaced by the console
+pub const SYSTEM_CONFIG: SharedResource = SharedResource {
+    name: "system_config",
+    users: &[
+        user(&REGULAR_PRODUCER, SYSTEM_CONFIG_CRITICAL_SECTION),
+        user(&ACTIVATION_LOG_READER, SYSTEM_CONFIG_CRITICAL_SECTION),
+        CONSOLE,
+    ],
+};
+
+pub const SHARED_RESOURCES: [SharedResource; 4] =
+    [REQUEST_BUFFER, ACTIVATION_LOG, DEADLINE_TIMER, SYSTEM_CONFIG];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ceiling_is_highest_user_priority() {
+        assert_eq!(REQUEST_BUFFER.ceiling(), 7);
+        assert_eq!(ACTIVATION_LOG.ceiling(), 11);
+        assert_eq!(DEADLINE_TIMER.ceiling(), DEADLINE_TIMER_PRIORITY);
+        assert_eq!(SYSTEM_CONFIG.ceiling(), 7);
+    }
+
+    #[test]
+    fn deadlines_do_not_exceed_periods() {
+        for task in TASKS {
+            assert!(task.deadline <= task.period(), "{}", task.name);
+        }
+    }
+}
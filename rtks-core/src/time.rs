@@ -0,0 +1,49 @@
+// Time base shared with the firmware monotonic, the types below are the same
+// as `<Mono as Monotonic>::Instant`/`Duration`: a 32 bit millisecond SysTick by
+// default, a 64 bit microsecond TIM2 with the `microsecond-tick` feature.
+#[cfg(not(feature = "microsecond-tick"))]
+pub const TICK_RATE_HZ: u32 = 1_000;
+#[cfg(not(feature = "microsecond-tick"))]
+pub type Instant = fugit::TimerInstantU32<TICK_RATE_HZ>;
+#[cfg(not(feature = "microsecond-tick"))]
+pub type Duration = fugit::TimerDurationU32<TICK_RATE_HZ>;
+
+#[cfg(feature = "microsecond-tick")]
+pub const TICK_RATE_HZ: u32 = 1_000_000;
+#[cfg(feature = "microsecond-tick")]
+pub type Instant = fugit::TimerInstantU64<TICK_RATE_HZ>;
+#[cfg(feature = "microsecond-tick")]
+pub type Duration = fugit::TimerDurationU64<TICK_RATE_HZ>;
+
+// Length of `duration` in microseconds
+#[cfg(not(feature = "microsecond-tick"))]
+pub fn to_micros(duration: Duration) -> u32 {
+    duration.to_micros()
+}
+#[cfg(feature = "microsecond-tick")]
+pub fn to_micros(duration: Duration) -> u32 {
+    duration.to_micros().try_into().unwrap_or(u32::MAX)
+}
+
+// Length of `duration` in milliseconds
+#[cfg(not(feature = "microsecond-tick"))]
+pub fn to_millis(duration: Duration) -> u32 {
+    duration.to_millis()
+}
+#[cfg(feature = "microsecond-tick")]
+pub fn to_millis(duration: Duration) -> u32 {
+    duration.to_millis().try_into().unwrap_or(u32::MAX)
+}
+
+// Duration of `micros` microseconds, truncated to the tick
+#[cfg(not(feature = "microsecond-tick"))]
+pub fn from_micros(micros: u32) -> Duration {
+    Duration::micros(micros)
+}
+#[cfg(feature = "microsecond-tick")]
+pub fn from_micros(micros: u32) -> Duration {
+    Duration::micros(u64::from(micros))
+}
+
+// Core clock, also the rate of the cycle counter used for job timing
+pub const SYSCLK_HZ: u32 = 168_000_000;
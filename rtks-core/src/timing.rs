@@ -0,0 +1,138 @@
+// Cycle-accurate job timing. Timestamps are raw values of the 32 bit core cycle
+// counter, so intervals are computed with wrapping arithmetic and are valid
+// as long as they are shorter than one counter period (~25 s at 168 MHz).
+use crate::time::SYSCLK_HZ;
+
+pub const CYCLES_PER_MICROSECOND: u32 = SYSCLK_HZ / 1_000_000;
+pub const CYCLES_PER_MILLISECOND: u32 = SYSCLK_HZ / 1_000;
+
+pub fn cycles_to_micros(cycles: u32) -> u32 {
+    cycles / CYCLES_PER_MICROSECOND
+}
+
+// Cycle counter values sampled along one job
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct JobTimestamps {
+    pub release: u32,
+    pub start: u32,
+    pub completion: u32,
+}
+
+impl JobTimestamps {
+    pub fn response_time(&self) -> u32 {
+        self.completion.wrapping_sub(self.release)
+    }
+
+    // Elapsed time from start to completion, it includes preemptions by
+    // higher priority tasks so it is an upper bound of the consumed CPU time
+    pub fn execution_time(&self) -> u32 {
+        self.completion.wrapping_sub(self.start)
+    }
+
+    // Delay from the nominal release to the start of the job
+    pub fn release_jitter(&self) -> u32 {
+        self.start.wrapping_sub(self.release)
+    }
+}
+
+// Min/max/average of a series of intervals, in cycles
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimingStatistics {
+    min: u32,
+    max: u32,
+    total: u64,
+    samples: u32,
+}
+
+impl TimingStatistics {
+    pub const fn new() -> Self {
+        TimingStatistics {
+            min: u32::MAX,
+            max: 0,
+            total: 0,
+            samples: 0,
+        }
+    }
+
+    pub fn record(&mut self, cycles: u32) {
+        self.min = self.min.min(cycles);
+        self.max = self.max.max(cycles);
+        self.total += cycles as u64;
+        self.samples += 1;
+    }
+
+    pub fn samples(&self) -> u32 {
+        self.samples
+    }
+
+    pub fn min(&self) -> Option<u32> {
+        (self.samples > 0).then_some(self.min)
+    }
+
+    // High-water mark
+    pub fn max(&self) -> Option<u32> {
+        (self.samples > 0).then_some(self.max)
+    }
+
+    pub fn average(&self) -> Option<u32> {
+        (self.samples > 0).then(|| (self.total / self.samples as u64) as u32)
+    }
+}
+
+impl Default for TimingStatistics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl defmt::Format for TimingStatistics {
+    fn format(&self, f: defmt::Formatter) {
+        if self.samples == 0 {
+            defmt::write!(f, "no samples");
+        } else {
+            defmt::write!(
+                f,
+                "min = {} us, avg = {} us, max = {} us ({} jobs)",
+                cycles_to_micros(self.min),
+                cycles_to_micros((self.total / self.samples as u64) as u32),
+                cycles_to_micros(self.max),
+                self.samples
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_statistics_have_no_values() {
+        let stats = TimingStatistics::new();
+        assert_eq!((stats.min(), stats.max(), stats.average()), (None, None, None));
+    }
+
+    #[test]
+    fn statistics_track_min_max_average() {
+        let mut stats = TimingStatistics::new();
+        for cycles in [300, 100, 200] {
+            stats.record(cycles);
+        }
+        assert_eq!(stats.min(), Some(100));
+        assert_eq!(stats.max(), Some(300));
+        assert_eq!(stats.average(), Some(200));
+        assert_eq!(stats.samples(), 3);
+    }
+
+    #[test]
+    fn job_times_survive_counter_wrap() {
+        let job = JobTimestamps {
+            release: u32::MAX - 99,
+            start: u32::MAX - 49,
+            completion: 150,
+        };
+        assert_eq!(job.response_time(), 250);
+        assert_eq!(job.execution_time(), 200);
+        assert_eq!(job.release_jitter(), 50);
+    }
+}
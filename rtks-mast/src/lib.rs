@@ -0,0 +1,281 @@
+// MAST model generation.
+//
+// Scheduling servers, shared resources and transactions are derived from
+// `rtks_core::task_set`, the same constants the firmware is built from. The
+// operations (with their measured WCETs), the processing resource and the
+// scheduler are still hand-written fragments in the `mast/` directory and are
+// appended to the generated part.
+// The model is of the nominal mode, `rtks-rta` analyses the other modes too.
+use std::{
+    fmt::Write as _,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use rtks_core::task_set::{
+    self, Activation, SharedResource, TaskParameters, CONSOLE_PRIORITY, CONSOLE_RX_PRIORITY,
+    DEADLINE_TIMER_PRIORITY, EXTERNAL_INTERRUPT_PRIORITY,
+};
+
+pub const OUTPUT_FILE: &str = "mast_configuration.txt";
+
+const HEADER: &str = "-- Generated by rtks-mast from rtks_core::task_set, do not edit.\n\
+                      -- Operations are taken from the fragments in the mast/ directory.\n";
+
+fn seconds(millis: u32, decimals: usize) -> String {
+    format!("{:.*}", decimals, millis as f64 / 1_000.0)
+}
+
+pub fn scheduling_server(task: &TaskParameters) -> String {
+    format!(
+        "Scheduling_Server (
+\tType => Regular,
+\tName => {name},
+\tServer_Sched_Parameters =>
+\t\t( Type => Fixed_Priority_Policy,
+\t\tThe_Priority => {priority},
+\t\tPreassigned => YES),
+\tScheduler => fps);
+",
+        name = task.name,
+        priority = task.priority,
+    )
+}
+
+pub fn shared_resource(resource: &SharedResource) -> String {
+    format!(
+        "Shared_Resource (
+\tType => Immediate_Ceiling_Resource,
+\tName => {name},
+\tCeiling => {ceiling},
+\tPreassigned => YES);
+",
+        name = resource.name,
+        ceiling = resource.ceiling(),
+    )
+}
+
+pub fn transaction(task: &TaskParameters) -> String {
+    let mnemonic = task.mnemonic;
+    let (external_event, handler_type) = match task.activation {
+        Activation::Periodic { period } => (
+            format!(
+                "( Type => Periodic,
+\t\t\tName => {mnemonic}_activation,
+\t\t\tPeriod => {period},
+\t\t\tMax_Jitter => 0.000,
+\t\t\tPhase => {phase})",
+                period = seconds(period, 3),
+                phase = seconds(task.offset, 3),
+            ),
+            "System_Timed_Activity",
+        ),
+        Activation::Sporadic { min_interarrival } => (
+            format!(
+                "( Type => Sporadic,
+\t\t\tName => {mnemonic}_activation,
+\t\t\tMin_Interarrival => {min_interarrival})",
+                min_interarrival = seconds(min_interarrival, 3),
+            ),
+            "Activity",
+        ),
+    };
+
+    format!(
+        "Transaction (
+\tType => regular,
+\tName => {mnemonic}_transaction,
+\tExternal_Events =>
+\t\t( {external_event}),
+\tInternal_Events =>
+\t\t( ( Type => Regular,
+\t\t\tName => {mnemonic}o1,
+\t\t\tTiming_Requirements =>
+\t\t\t\t( Type => Hard_Global_Deadline,
+\t\t\t\tDeadline => {deadline},
+\t\t\t\tReferenced_Event => {mnemonic}_activation))),
+\tEvent_Handlers =>
+\t\t( (Type => {handler_type},
+\t\t\tInput_Event => {mnemonic}_activation,
+\t\t\tOutput_Event => {mnemonic}o1,
+\t\t\tActivity_Operation => {mnemonic}_job,
+\t\t\tActivity_Server => {name})));
+",
+        deadline = seconds(task.deadline, 6),
+        name = task.name,
+    )
+}
+
+// Hand-written fragments, in a stable order
+pub fn fragments(mast_dir: &Path) -> io::Result<Vec<PathBuf>> {
+    fn collect(dir: &Path, found: &mut Vec<PathBuf>) -> io::Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                collect(&path, found)?;
+            } else if path.extension().is_some_and(|ext| ext == "txt")
+                && path.file_name().is_some_and(|name| name != OUTPUT_FILE)
+            {
+                found.push(path);
+            }
+        }
+        Ok(())
+    }
+
+    let mut found = Vec::new();
+    collect(mast_dir, &mut found)?;
+    found.sort();
+    Ok(found)
+}
+
+pub fn generate(mast_dir: &Path) -> io::Result<String> {
+    let mut model = String::from(HEADER);
+
+    for task in task_set::TASKS.iter() {
+        write!(model, "\n{}", scheduling_server(task)).unwrap();
+    }
+    for resource in task_set::SHARED_RESOURCES.iter() {
+        write!(model, "\n{}", shared_resource(resource)).unwrap();
+    }
+    for task in task_set::TASKS.iter() {
+        write!(model, "\n{}", transaction(task)).unwrap();
+    }
+    for fragment in fragments(mast_dir)? {
+        let text = fs::read_to_string(&fragment)?;
+        write!(model, "\n{}\n", text.trim_end()).unwrap();
+    }
+
+    Ok(model)
+}
+
+// Priorities written in the `#[task(...)]` attributes of the RTIC application,
+// as (task name, priority) pairs
+pub fn rtic_priorities(main_rs: &str) -> Vec<(String, u8)> {
+    let mut priorities = Vec::new();
+    let mut pending = None;
+    for line in main_rs.lines().map(str::trim) {
+        if let Some(attribute) = line.strip_prefix("#[task(") {
+            pending = attribute
+                .split(',')
+                .filter_map(|argument| argument.trim().strip_prefix("priority"))
+                .filter_map(|value| value.trim().strip_prefix('='))
+                .find_map(|value| value.trim().trim_end_matches(")]").parse().ok());
+        } else if let Some(priority) = pending {
+            let signature = line.strip_prefix("async ").unwrap_or(line);
+            if let Some(rest) = signature.strip_prefix("fn ") {
+                let name = rest.split(['(', '<']).next().unwrap_or_default();
+                priorities.push((name.to_string(), priority));
+                pending = None;
+            }
+        }
+    }
+    priorities
+}
+
+fn expected_priority(rtic_task: &str) -> Option<u8> {
+    if let Some(task) = task_set::TASKS.iter().find(|task| task.name == rtic_task) {
+        Some(task.priority)
+    } else if rtic_task == "deadline_timer" {
+        Some(DEADLINE_TIMER_PRIORITY)
+    } else if rtic_task == "external_interrupt" {
+        Some(EXTERNAL_INTERRUPT_PRIORITY)
+    } else if rtic_task == "console" {
+        Some(CONSOLE_PRIORITY)
+    } else if rtic_task == "console_rx" {
+        Some(CONSOLE_RX_PRIORITY)
+    } else {
+        None
+    }
+}
+
+// Mismatches between the RTIC application and the task set, one message each
+pub fn check_priorities(main_rs: &str) -> Vec<String> {
+    let declared = rtic_priorities(main_rs);
+    let mut mismatches = Vec::new();
+
+    for (name, priority) in declared.iter() {
+        match expected_priority(name) {
+            Some(expected) if expected != *priority => mismatches.push(format!(
+                "task `{}` has priority {} in main.rs but {} in the task set",
+                name, priority, expected
+            )),
+            _ => {}
+        }
+    }
+    for task in task_set::TASKS.iter() {
+        if !declared.iter().any(|(name, _)| name == task.name) {
+            mismatches.push(format!("task `{}` is not declared in main.rs", task.name));
+        }
+    }
+
+    mismatches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workspace_root() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap().to_path_buf()
+    }
+
+    #[test]
+    fn firmware_priorities_match_task_set() {
+        let main_rs = fs::read_to_string(workspace_root().join("src/main.rs")).unwrap();
+        assert_eq!(check_priorities(&main_rs), Vec::<String>::new());
+    }
+
+    #[test]
+    fn rtic_priorities_are_parsed() {
+        let main_rs = "
+            #[task(binds = TIM5, priority = 13, local = [timer])]
+            fn external_interrupt(cx: external_interrupt::Context) {}
+
+            #[task(priority = 7, shared = [request_buffer])]
+            async fn regular_producer(mut cx: regular_producer::Context) {}
+        ";
+        assert_eq!(
+            rtic_priorities(main_rs),
+            [
+                ("external_interrupt".to_string(), 13),
+                ("regular_producer".to_string(), 7)
+            ]
+        );
+    }
+
+    #[test]
+    fn mismatching_priority_is_reported() {
+        let main_rs = "
+            #[task(priority = 4)]
+            async fn regular_producer(cx: regular_producer::Context) {}
+        ";
+        let mismatches = check_priorities(main_rs);
+        assert!(mismatches[0].contains("`regular_producer` has priority 4"));
+    }
+
+    #[test]
+    fn periodic_transaction_uses_period_and_deadline() {
+        let transaction = transaction(&task_set::REGULAR_PRODUCER);
+        assert!(transaction.contains("Period => 1.000"));
+        assert!(transaction.contains("Deadline => 0.500000"));
+        assert!(transaction.contains("Phase => 0.000"));
+        assert!(transaction.contains("Activity_Server => regular_producer"));
+    }
+
+    #[test]
+    fn shared_resource_uses_srp_ceiling() {
+        assert!(shared_resource(&task_set::REQUEST_BUFFER).contains("Ceiling => 7"));
+    }
+
+    #[test]
+    fn committed_model_is_up_to_date() {
+        let mast_dir = workspace_root().join("mast");
+        let committed = fs::read_to_string(mast_dir.join(OUTPUT_FILE)).unwrap();
+        assert_eq!(
+            committed,
+            generate(&mast_dir).unwrap(),
+            "mast/{} is stale, regenerate it with `cargo mast`",
+            OUTPUT_FILE
+        );
+    }
+}
@@ -0,0 +1,45 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use rtks_mast::{OUTPUT_FILE, check_priorities, generate};
+
+fn main() -> ExitCode {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("rtks-mast lives inside the workspace");
+    let mast_dir = std::env::args()
+        .nth(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| root.join("mast"));
+
+    // Refuse to model a task set the firmware does not implement
+    let main_rs = match fs::read_to_string(root.join("src/main.rs")) {
+        Ok(main_rs) => main_rs,
+        Err(err) => {
+            eprintln!("Cannot read src/main.rs: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+    let mismatches = check_priorities(&main_rs);
+    if !mismatches.is_empty() {
+        for mismatch in mismatches {
+            eprintln!("{}", mismatch);
+        }
+        return ExitCode::FAILURE;
+    }
+
+    let output = mast_dir.join(OUTPUT_FILE);
+    match generate(&mast_dir).and_then(|model| fs::write(&output, model)) {
+        Ok(()) => {
+            println!("MAST configuration generated in {}", output.display());
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("Cannot generate the MAST configuration: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
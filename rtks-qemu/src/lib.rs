@@ -0,0 +1,274 @@
+// Host-side runner for the firmware integration tests.
+//
+// The firmware is built with the `bounded-run` feature, so that after
+// `RTKS_RUN_DURATION_MS` it exits QEMU through semihosting. The defmt frames it
+// prints on stdout are decoded with `defmt-print` and turned into `Record`s the
+// tests can assert on.
+use std::{
+    fmt,
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    process::{Command, ExitStatus, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+const TARGET: &str = "thumbv7em-none-eabihf";
+const FIRMWARE: &str = "rtks";
+// Separate target directory, so bounded-run builds do not invalidate the regular ones
+const TARGET_DIR: &str = "target/qemu";
+// Wall-clock guard against a firmware that never exits
+const HOST_TIMEOUT_FACTOR: u32 = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Level {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl Level {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "TRACE" => Some(Level::Trace),
+            "DEBUG" => Some(Level::Debug),
+            "INFO" => Some(Level::Info),
+            "WARN" => Some(Level::Warn),
+            "ERROR" => Some(Level::Error),
+            _ => None,
+        }
+    }
+}
+
+// A decoded defmt log record
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Record {
+    pub level: Level,
+    pub message: String,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Io(&'static str, io::Error),
+    Build(ExitStatus),
+    Timeout(Duration),
+    Decode(ExitStatus),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(what, err) => write!(f, "failed to run {}: {}", what, err),
+            Error::Build(status) => write!(f, "firmware build failed: {}", status),
+            Error::Timeout(timeout) => write!(f, "firmware did not exit within {:?}", timeout),
+            Error::Decode(status) => write!(f, "defmt-print failed: {}", status),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+// Outcome of a bounded run of the firmware
+pub struct Run {
+    pub status: ExitStatus,
+    pub records: Vec<Record>,
+}
+
+impl Run {
+    // Records whose message starts with `prefix`
+    pub fn matching<'a>(&'a self, prefix: &'a str) -> impl Iterator<Item = &'a Record> + 'a {
+        self.records
+            .iter()
+            .filter(move |record| record.message.starts_with(prefix))
+    }
+
+    pub fn count(&self, prefix: &str) -> usize {
+        self.matching(prefix).count()
+    }
+}
+
+fn workspace_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("rtks-qemu lives inside the workspace")
+        .to_path_buf()
+}
+
+// Build the firmware so that it stops itself after `duration_ms` milliseconds of simulated time
+pub fn build(duration_ms: u32, features: &[&str]) -> Result<PathBuf, Error> {
+    let root = workspace_root();
+    let mut all_features = vec!["bounded-run"];
+    all_features.extend_from_slice(features);
+
+    let status = Command::new(env!("CARGO"))
+        .current_dir(&root)
+        .args(["build", "--release", "-p", FIRMWARE, "--target", TARGET])
+        .arg("--features")
+        .arg(all_features.join(","))
+        .arg("--target-dir")
+        .arg(TARGET_DIR)
+        .env("RTKS_RUN_DURATION_MS", duration_ms.to_string())
+        .status()
+        .map_err(|err| Error::Io("cargo build", err))?;
+    if !status.success() {
+        return Err(Error::Build(status));
+    }
+
+    Ok(root
+        .join(TARGET_DIR)
+        .join(TARGET)
+        .join("release")
+        .join(FIRMWARE))
+}
+
+// Boot `elf` on QEMU, wait for the semihosting exit and decode the defmt stream
+pub fn run(elf: &Path, duration_ms: u32) -> Result<Run, Error> {
+    let mut qemu = Command::new("qemu-system-arm")
+        .args([
+            "-cpu",
+            "cortex-m4",
+            "-machine",
+            "olimex-stm32-h405",
+            "-nographic",
+        ])
+        .args(["-semihosting-config", "enable=on,target=native"])
+        .arg("-kernel")
+        .arg(elf)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|err| Error::Io("qemu-system-arm", err))?;
+
+    let mut stdout = qemu.stdout.take().expect("qemu stdout is piped");
+    let reader = thread::spawn(move || {
+        let mut frames = Vec::new();
+        stdout.read_to_end(&mut frames).map(|_| frames)
+    });
+
+    let timeout = Duration::from_millis(u64::from(duration_ms) * u64::from(HOST_TIMEOUT_FACTOR))
+        .max(Duration::from_secs(30));
+    let started = Instant::now();
+    let status = loop {
+        match qemu
+            .try_wait()
+            .map_err(|err| Error::Io("qemu-system-arm", err))?
+        {
+            Some(status) => break status,
+            None if started.elapsed() > timeout => {
+                let _ = qemu.kill();
+                let _ = qemu.wait();
+                return Err(Error::Timeout(timeout));
+            }
+            None => thread::sleep(Duration::from_millis(50)),
+        }
+    };
+    let frames = reader
+        .join()
+        .expect("qemu stdout reader panicked")
+        .map_err(|err| Error::Io("qemu-system-arm", err))?;
+
+    Ok(Run {
+        status,
+        records: decode(elf, &frames)?,
+    })
+}
+
+// Convenience wrapper around `build` and `run`
+pub fn build_and_run(duration_ms: u32, features: &[&str]) -> Result<Run, Error> {
+    let elf = build(duration_ms, features)?;
+    run(&elf, duration_ms)
+}
+
+fn decode(elf: &Path, frames: &[u8]) -> Result<Vec<Record>, Error> {
+    let mut defmt_print = Command::new("defmt-print")
+        .arg("-e")
+        .arg(elf)
+        .args(["--log-format", "{L} {s}"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|err| Error::Io("defmt-print", err))?;
+
+    let mut stdin = defmt_print
+        .stdin
+        .take()
+        .expect("defmt-print stdin is piped");
+    stdin
+        .write_all(frames)
+        .map_err(|err| Error::Io("defmt-print", err))?;
+    drop(stdin);
+
+    let output = defmt_print
+        .wait_with_output()
+        .map_err(|err| Error::Io("defmt-print", err))?;
+    if !output.status.success() {
+        return Err(Error::Decode(output.status));
+    }
+
+    Ok(parse(&String::from_utf8_lossy(&output.stdout)))
+}
+
+fn parse(decoded: &str) -> Vec<Record> {
+    strip_ansi(decoded)
+        .lines()
+        .filter_map(|line| {
+            let (level, message) = line.trim_start().split_once(' ')?;
+            Some(Record {
+                level: Level::parse(level)?,
+                message: message.trim().to_string(),
+            })
+        })
+        .collect()
+}
+
+// defmt-print may colour the level even when its output is not a terminal
+fn strip_ansi(decoded: &str) -> String {
+    let mut plain = String::with_capacity(decoded.len());
+    let mut chars = decoded.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            // Skip the CSI sequence up to its final byte
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            plain.push(c);
+        }
+    }
+    plain
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_skips_lines_without_level() {
+        let records =
+            parse("INFO  Init\nsome qemu noise\nERROR Deadline miss detected for task 'A'.\n");
+        assert_eq!(
+            records,
+            [
+                Record {
+                    level: Level::Info,
+                    message: "Init".to_string()
+                },
+                Record {
+                    level: Level::Error,
+                    message: "Deadline miss detected for task 'A'.".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_ignores_colours() {
+        let records = parse("\u{1b}[32mINFO \u{1b}[0m End of cyclic activation.\n");
+        assert_eq!(records[0].level, Level::Info);
+        assert_eq!(records[0].message, "End of cyclic activation.");
+    }
+}
@@ -0,0 +1,91 @@
+// Boot the firmware built with the `fault-injection` feature and check that the
+// injected faults are detected, and only them.
+// Needs `qemu-system-arm` and `defmt-print` on the PATH, run with `cargo test-qemu`.
+use std::sync::OnceLock;
+
+use rtks_qemu::Run;
+
+// Long enough for the first external interrupt, raised 5 to 7 s after boot
+const RUN_DURATION_MS: u32 = 10_000;
+// Mirrors the firmware constants: release delay of the first on call producer
+// job, 35 ms, and the interrupts flooded after the first one
+const ON_CALL_PRODUCER_JITTER_US: u32 = 35_000;
+const FLOODED_INTERRUPTS: u32 = 5;
+
+fn run() -> &'static Run {
+    static RUN: OnceLock<Run> = OnceLock::new();
+    RUN.get_or_init(|| {
+        rtks_qemu::build_and_run(RUN_DURATION_MS, &["fault-injection"])
+            .unwrap_or_else(|err| panic!("{}", err))
+    })
+}
+
+#[test]
+#[ignore = "requires qemu-system-arm and defmt-print"]
+fn scenario_is_loaded() {
+    assert_eq!(run().count("Fault injection:"), 4);
+}
+
+#[test]
+#[ignore = "requires qemu-system-arm and defmt-print"]
+fn suppressed_cancellation_is_detected_exactly_once() {
+    let misses: Vec<_> = run()
+        .matching("Deadline miss detected for job")
+        .filter(|record| record.message.contains("'regular_producer'"))
+        .map(|record| record.message.as_str())
+        .collect();
+    assert_eq!(misses.len(), 1, "{:#?}", misses);
+    assert!(misses[0].starts_with("Deadline miss detected for job 3 of task"), "{}", misses[0]);
+}
+
+#[test]
+#[ignore = "requires qemu-system-arm and defmt-print"]
+fn injected_misses_fail_the_run() {
+    assert!(!run().status.success(), "QEMU exited with {}", run().status);
+    assert_eq!(run().count("Run result: FAIL"), 1);
+}
+
+#[test]
+#[ignore = "requires qemu-system-arm and defmt-print"]
+fn inflated_workload_overrun_is_reported() {
+    // QEMU leaves the cycle counter stopped, budgets fall back to the monotonic
+    assert_eq!(run().count("Cycle counter stopped"), 1);
+    assert_eq!(
+        run().count("Budget overrun for job 2 of task 'activation_log_reader'"),
+        1
+    );
+    assert_eq!(run().count("Activation log reader job aborted."), 1);
+}
+
+#[test]
+#[ignore = "requires qemu-system-arm and defmt-print"]
+fn release_jitter_shows_in_the_summary() {
+    let summary = run()
+        .matching("Task 'on_call_producer': release jitter")
+        .map(|record| record.message.as_str())
+        .next()
+        .expect("no release jitter of the on call producer");
+    let max: u32 = summary
+        .split("max = ")
+        .nth(1)
+        .and_then(|rest| rest.split(' ').next())
+        .and_then(|max| max.parse().ok())
+        .unwrap_or_else(|| panic!("no max in '{}'", summary));
+    assert!(max >= ON_CALL_PRODUCER_JITTER_US, "{}", summary);
+}
+
+#[test]
+#[ignore = "requires qemu-system-arm and defmt-print"]
+fn flooded_events_are_rejected() {
+    let rejected = run()
+        .matching("Release of task 'external_event_server' earlier than its minimum interarrival")
+        .count() as u32;
+    assert_eq!(rejected, FLOODED_INTERRUPTS);
+    assert_eq!(
+        run().count(&format!(
+            "Task 'external_event_server': {} releases earlier than the minimum interarrival",
+            FLOODED_INTERRUPTS
+        )),
+        1
+    );
+}
@@ -0,0 +1,124 @@
+// Boot the firmware on QEMU and check its behaviour through the defmt log.
+// Needs `qemu-system-arm` and `defmt-print` on the PATH, run with `cargo test-qemu`.
+use std::sync::OnceLock;
+
+use rtks_qemu::{Level, Run};
+
+const RUN_DURATION_MS: u32 = 12_000;
+// Mirrors the firmware constants
+const RELATIVE_OFFSET_MS: u32 = 1_000;
+const REGULAR_PRODUCER_PERIOD_MS: u32 = 1_000;
+
+fn run() -> &'static Run {
+    static RUN: OnceLock<Run> = OnceLock::new();
+    RUN.get_or_init(|| {
+        rtks_qemu::build_and_run(RUN_DURATION_MS, &[]).unwrap_or_else(|err| panic!("{}", err))
+    })
+}
+
+#[test]
+#[ignore = "requires qemu-system-arm and defmt-print"]
+fn firmware_exits_through_semihosting() {
+    assert!(run().status.success(), "QEMU exited with {}", run().status);
+    assert_eq!(run().count("End of run."), 1);
+    assert_eq!(run().count("Run result: PASS"), 1);
+}
+
+#[test]
+#[ignore = "requires qemu-system-arm and defmt-print"]
+fn workload_is_calibrated_before_the_epoch() {
+    // Timed with the monotonic, the cycle counter does not run on QEMU
+    assert_eq!(run().count("Workload calibrated:"), 1);
+    assert_eq!(run().count("Workload not calibrated"), 0);
+    let position = |prefix| {
+        run().records.iter().position(|record| record.message.starts_with(prefix))
+    };
+    assert!(position("Workload calibrated:") < position("End of cyclic activation."));
+}
+
+#[test]
+#[ignore = "requires qemu-system-arm and defmt-print"]
+fn summary_reports_every_task() {
+    for task in [
+        "activation_log_reader",
+        "external_event_server",
+        "on_call_producer",
+        "regular_producer",
+    ] {
+        assert_eq!(run().count(&format!("Task '{}': activations", task)), 1, "{}", task);
+    }
+    assert_eq!(run().count("Request buffer:"), 1);
+}
+
+#[test]
+#[ignore = "requires qemu-system-arm and defmt-print"]
+fn regular_producer_completes_every_period() {
+    let expected = (RUN_DURATION_MS - RELATIVE_OFFSET_MS) / REGULAR_PRODUCER_PERIOD_MS;
+    let completed = run().count("End of cyclic activation.") as u32;
+    // Allow for the job in progress when the run ends
+    assert!(
+        completed + 1 >= expected,
+        "{} cyclic activations, expected at least {}",
+        completed,
+        expected - 1
+    );
+}
+
+#[test]
+#[ignore = "requires qemu-system-arm and defmt-print"]
+fn no_deadline_is_missed() {
+    let misses: Vec<_> = run().matching("Deadline miss detected").collect();
+    assert!(misses.is_empty(), "{:#?}", misses);
+}
+
+#[test]
+#[ignore = "requires qemu-system-arm and defmt-print"]
+fn no_error_is_logged() {
+    let errors: Vec<_> = run()
+        .records
+        .iter()
+        .filter(|record| record.level == Level::Error)
+        .collect();
+    assert!(errors.is_empty(), "{:#?}", errors);
+}
+
+#[test]
+#[ignore = "requires qemu-system-arm and defmt-print"]
+fn sporadic_tasks_respect_their_minimum_interarrival() {
+    let violations: Vec<_> = run()
+        .matching("Release of task")
+        .filter(|record| record.message.contains("minimum interarrival"))
+        .collect();
+    assert!(violations.is_empty(), "{:#?}", violations);
+}
+
+#[test]
+#[ignore = "requires qemu-system-arm and defmt-print"]
+fn activation_log_entries_are_read_once_and_never_dropped() {
+    let dropped: Vec<_> = run()
+        .matching("Activation log reader:")
+        .filter(|record| record.message.contains("overwritten"))
+        .collect();
+    assert!(dropped.is_empty(), "{:#?}", dropped);
+
+    let sequences: Vec<&str> = run()
+        .matching("Activation log reader: LogEntry")
+        .filter_map(|record| record.message.split("sequence: ").nth(1))
+        .filter_map(|rest| rest.split(',').next())
+        .collect();
+    let mut distinct = sequences.clone();
+    distinct.dedup();
+    assert_eq!(sequences, distinct);
+}
+
+#[test]
+#[ignore = "requires qemu-system-arm and defmt-print"]
+fn stack_usage_is_reported_for_every_task_priority() {
+    // The stack monitor reports twice during the run, the summary once more
+    assert!(run().count("Stack: high-water mark") >= 3);
+    let near_overflow: Vec<_> = run().matching("near overflow").collect();
+    assert!(near_overflow.is_empty(), "{:#?}", near_overflow);
+    for priority in [3, 5, 7, 11, 12, 13] {
+        assert_eq!(run().count(&format!("Stack: priority {} sampled", priority)), 1, "{}", priority);
+    }
+}
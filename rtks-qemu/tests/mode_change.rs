@@ -0,0 +1,46 @@
+// Boot the firmware built with the `mode-schedule` feature and check that the
+// scheduled mode changes are carried out safely.
+// Needs `qemu-system-arm` and `defmt-print` on the PATH, run with `cargo test-qemu`.
+use std::sync::OnceLock;
+
+use rtks_qemu::{Level, Run};
+
+// Long enough for the last change of the schedule, requested 9 s after boot
+const RUN_DURATION_MS: u32 = 12_000;
+
+fn run() -> &'static Run {
+    static RUN: OnceLock<Run> = OnceLock::new();
+    RUN.get_or_init(|| {
+        rtks_qemu::build_and_run(RUN_DURATION_MS, &["mode-schedule"])
+            .unwrap_or_else(|err| panic!("{}", err))
+    })
+}
+
+#[test]
+#[ignore = "requires qemu-system-arm and defmt-print"]
+fn scheduled_mode_changes_are_carried_out_in_order() {
+    let changes: Vec<_> = run()
+        .matching("Mode change:")
+        .map(|record| record.message.as_str())
+        .collect();
+    assert_eq!(
+        changes,
+        [
+            "Mode change: nominal -> high-load (Schedule)",
+            "Mode change: high-load -> degraded (Schedule)",
+            "Mode change: degraded -> nominal (Schedule)",
+        ]
+    );
+}
+
+#[test]
+#[ignore = "requires qemu-system-arm and defmt-print"]
+fn no_deadline_or_budget_is_exceeded_across_mode_changes() {
+    let errors: Vec<_> = run()
+        .records
+        .iter()
+        .filter(|record| record.level == Level::Error || record.message.starts_with("Deadline miss"))
+        .collect();
+    assert!(errors.is_empty(), "{:#?}", errors);
+    assert_eq!(run().count("Run result: PASS"), 1);
+}
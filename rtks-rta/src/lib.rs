@@ -0,0 +1,233 @@
+// Fixed-priority response-time analysis under the Stack Resource Policy.
+//
+// The worst-case response time of task i is the least fixed point of
+//     R_i = C_i + B_i + sum_{j in hp(i)} ceil(R_i / T_j) * C_j
+// where hp(i) are the other tasks with priority not lower than i (RTIC runs
+// tasks of equal priority to completion one after the other) and B_i is the
+// longest critical section of a lower priority task on a resource whose
+// ceiling is at least the priority of i, the only blocking SRP allows.
+// Release offsets are ignored: the synchronous release they may avoid is the
+// critical instant, so the bounds stay safe.
+// Each mode of the system is a task set of its own: the mode change protocol
+// waits for the sporadic jobs of the old mode to complete before switching.
+// All times are in microseconds.
+use std::cmp::Reverse;
+
+use rtks_core::mode::SystemMode;
+use rtks_core::task_set::{
+    self, DEADLINE_TIMER_PRIORITY, DEADLINE_TIMER_WCET, EXTERNAL_EVENT_SERVER,
+    EXTERNAL_INTERRUPT_PRIORITY, EXTERNAL_INTERRUPT_WCET, CONSOLE_CHARACTER_TIME,
+    CONSOLE_RX_PRIORITY, CONSOLE_RX_WCET, SharedResource,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Task {
+    pub name: String,
+    pub priority: u8,
+    // Period or minimum interarrival
+    pub period: u64,
+    pub deadline: u64,
+    pub wcet: u64,
+}
+
+pub struct TaskSet {
+    pub tasks: Vec<Task>,
+    pub resources: Vec<SharedResource>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResponseTime {
+    pub name: String,
+    pub priority: u8,
+    pub deadline: u64,
+    pub blocking: u64,
+    // None when the recurrence exceeds the deadline
+    pub wcrt: Option<u64>,
+}
+
+impl ResponseTime {
+    pub fn schedulable(&self) -> bool {
+        self.wcrt.is_some()
+    }
+
+    pub fn slack(&self) -> Option<u64> {
+        self.wcrt.map(|wcrt| self.deadline - wcrt)
+    }
+}
+
+const MICROS_PER_MILLI: u64 = 1_000;
+
+// The firmware task set in `mode`, including the deadline timer service (modelled as one
+// job per release of each task), the external interrupt and the console receive
+// interrupt, which preempt the application tasks. The console interpreter runs
+// in the background, it only adds blocking through its critical sections
+pub fn rtks_task_set(mode: SystemMode) -> TaskSet {
+    let parameters = mode.parameters();
+    let released = || task_set::TASKS.iter().filter(|task| parameters.releases(task));
+    let mut tasks: Vec<Task> = released()
+        .map(|task| Task {
+            name: task.name.to_string(),
+            priority: task.priority,
+            period: parameters.period(task) as u64 * MICROS_PER_MILLI,
+            deadline: parameters.deadline(task) as u64 * MICROS_PER_MILLI,
+            wcet: parameters.wcet(task) as u64,
+        })
+        .collect();
+
+    for task in released() {
+        tasks.push(Task {
+            name: format!("deadline_timer ({})", task.name),
+            priority: DEADLINE_TIMER_PRIORITY,
+            period: parameters.period(task) as u64 * MICROS_PER_MILLI,
+            deadline: parameters.period(task) as u64 * MICROS_PER_MILLI,
+            wcet: DEADLINE_TIMER_WCET as u64,
+        });
+    }
+    tasks.push(Task {
+        name: "external_interrupt".to_string(),
+        priority: EXTERNAL_INTERRUPT_PRIORITY,
+        period: EXTERNAL_EVENT_SERVER.period() as u64 * MICROS_PER_MILLI,
+        deadline: EXTERNAL_EVENT_SERVER.period() as u64 * MICROS_PER_MILLI,
+        wcet: EXTERNAL_INTERRUPT_WCET as u64,
+    });
+    // Characters received back to back at the console baud rate
+    tasks.push(Task {
+        name: "console_rx".to_string(),
+        priority: CONSOLE_RX_PRIORITY,
+        period: CONSOLE_CHARACTER_TIME as u64,
+        deadline: CONSOLE_CHARACTER_TIME as u64,
+        wcet: CONSOLE_RX_WCET as u64,
+    });
+
+    TaskSet {
+        tasks,
+        resources: task_set::SHARED_RESOURCES.to_vec(),
+    }
+}
+
+pub fn blocking(resources: &[SharedResource], priority: u8) -> u64 {
+    resources
+        .iter()
+        .filter(|resource| resource.ceiling() >= priority)
+        .flat_map(|resource| resource.users.iter())
+        .filter(|user| user.priority < priority)
+        .map(|user| user.critical_section as u64)
+        .max()
+        .unwrap_or(0)
+}
+
+pub fn response_time(task_set: &TaskSet, index: usize) -> ResponseTime {
+    let task = &task_set.tasks[index];
+    let blocking = blocking(&task_set.resources, task.priority);
+    let higher_priority: Vec<&Task> = task_set
+        .tasks
+        .iter()
+        .enumerate()
+        .filter(|&(other, candidate)| other != index && candidate.priority >= task.priority)
+        .map(|(_, candidate)| candidate)
+        .collect();
+
+    let mut wcrt = task.wcet + blocking;
+    let wcrt = loop {
+        let next = task.wcet
+            + blocking
+            + higher_priority
+                .iter()
+                .map(|other| wcrt.div_ceil(other.period) * other.wcet)
+                .sum::<u64>();
+        if next > task.deadline {
+            break None;
+        }
+        if next == wcrt {
+            break Some(wcrt);
+        }
+        wcrt = next;
+    };
+
+    ResponseTime {
+        name: task.name.clone(),
+        priority: task.priority,
+        deadline: task.deadline,
+        blocking,
+        wcrt,
+    }
+}
+
+// Response times of every task, from the highest priority down
+pub fn analyse(task_set: &TaskSet) -> Vec<ResponseTime> {
+    let mut response_times: Vec<ResponseTime> = (0..task_set.tasks.len())
+        .map(|index| response_time(task_set, index))
+        .collect();
+    response_times.sort_by_key(|response_time| Reverse(response_time.priority));
+    response_times
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rtks_core::task_set::ResourceUser;
+
+    fn task(name: &str, priority: u8, period: u64, wcet: u64) -> Task {
+        Task {
+            name: name.to_string(),
+            priority,
+            period,
+            deadline: period,
+            wcet,
+        }
+    }
+
+    #[test]
+    fn textbook_task_set() {
+        // Buttazzo, Hard Real-Time Computing Systems, example 4.9
+        let task_set = TaskSet {
+            tasks: vec![task("t1", 3, 4, 1), task("t2", 2, 6, 2), task("t3", 1, 10, 3)],
+            resources: vec![],
+        };
+        let wcrts: Vec<_> = analyse(&task_set).iter().map(|rt| rt.wcrt).collect();
+        assert_eq!(wcrts, [Some(1), Some(3), Some(10)]);
+    }
+
+    #[test]
+    fn overloaded_task_is_not_schedulable() {
+        let task_set = TaskSet {
+            tasks: vec![task("t1", 2, 4, 3), task("t2", 1, 5, 2)],
+            resources: vec![],
+        };
+        let low = response_time(&task_set, 1);
+        assert!(!low.schedulable());
+        assert_eq!(low.slack(), None);
+    }
+
+    #[test]
+    fn blocking_only_from_lower_priority_users_under_ceiling() {
+        const RESOURCE: SharedResource = SharedResource {
+            name: "r",
+            users: &[
+                ResourceUser { priority: 2, critical_section: 7 },
+                ResourceUser { priority: 5, critical_section: 3 },
+            ],
+        };
+        assert_eq!(blocking(&[RESOURCE], 5), 7);
+        assert_eq!(blocking(&[RESOURCE], 3), 7);
+        // Above the ceiling there is no blocking
+        assert_eq!(blocking(&[RESOURCE], 6), 0);
+        // Nobody below the lowest priority user
+        assert_eq!(blocking(&[RESOURCE], 2), 0);
+    }
+
+    #[test]
+    fn rtks_task_set_is_schedulable_in_every_mode() {
+        for mode in SystemMode::ALL {
+            for response_time in analyse(&rtks_task_set(mode)) {
+                assert!(response_time.schedulable(), "{:?}: {:?}", mode, response_time);
+            }
+        }
+    }
+
+    #[test]
+    fn degraded_mode_sheds_the_on_call_producer() {
+        let task_set = rtks_task_set(SystemMode::Degraded);
+        assert!(task_set.tasks.iter().all(|task| !task.name.contains("on_call_producer")));
+    }
+}
@@ -0,0 +1,43 @@
+use std::process::ExitCode;
+
+use rtks_core::mode::SystemMode;
+use rtks_rta::{analyse, rtks_task_set};
+
+fn main() -> ExitCode {
+    let mut schedulable = true;
+    for mode in SystemMode::ALL {
+        let response_times = analyse(&rtks_task_set(mode));
+
+        println!("Mode {}", mode.name());
+        println!(
+            "{:<44} {:>8} {:>12} {:>12} {:>12} {:>12}",
+            "task", "priority", "deadline", "blocking", "wcrt", "slack"
+        );
+        for response_time in response_times.iter() {
+            let (wcrt, slack) = match (response_time.wcrt, response_time.slack()) {
+                (Some(wcrt), Some(slack)) => (wcrt.to_string(), slack.to_string()),
+                _ => ("> deadline".to_string(), "-".to_string()),
+            };
+            println!(
+                "{:<44} {:>8} {:>12} {:>12} {:>12} {:>12}",
+                response_time.name,
+                response_time.priority,
+                response_time.deadline,
+                response_time.blocking,
+                wcrt,
+                slack
+            );
+        }
+        println!();
+        schedulable &= response_times.iter().all(|response_time| response_time.schedulable());
+    }
+    println!("(times in microseconds)");
+
+    if schedulable {
+        println!("Task set is schedulable in every mode.");
+        ExitCode::SUCCESS
+    } else {
+        println!("Task set is NOT schedulable in every mode.");
+        ExitCode::FAILURE
+    }
+}
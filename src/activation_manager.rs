@@ -1,18 +1,65 @@
+use core::cell::Cell;
+use cortex_m::interrupt::{self, Mutex};
 use crate::{
+    cycle_counter,
+    deadline::{DeadlineHandle, DeadlineTimer},
     time::{Mono, Instant},
 };
 use rtic_monotonics::{Monotonic, fugit::ExtU32};
+use rtks_core::{interarrival::Admission, task_set::TaskParameters};
 
+// Delay from init to the start of the task set, milliseconds
 const RELATIVE_OFFSET: u32 = 1_000;
 
-pub fn activation_time() -> Instant {
-    Mono::now() + RELATIVE_OFFSET.millis()
+// System start epoch, common to all the tasks
+static EPOCH: Mutex<Cell<Option<Instant>>> = Mutex::new(Cell::new(None));
+
+// Fix the epoch, once in init before the tasks are spawned
+pub fn init() -> Instant {
+    let epoch = Mono::now() + RELATIVE_OFFSET.millis();
+    interrupt::free(|cs| EPOCH.borrow(cs).set(Some(epoch)));
+    epoch
+}
+
+pub fn epoch() -> Instant {
+    interrupt::free(|cs| EPOCH.borrow(cs).get()).expect("System epoch not set")
+}
+
+// Start of `task`, its offset (phase) after the epoch
+pub fn activation_time(task: &TaskParameters) -> Instant {
+    epoch() + task.offset.millis()
+}
+
+// A periodic task is first released at its activation time, returned so that
+// the following releases are computed from it
+pub async fn activation_cyclic(task: &TaskParameters) -> Instant {
+    let release = activation_time(task);
+    Mono::delay_until(release).await;
+    release
 }
 
-pub async fn activation_sporadic() {
-    Mono::delay_until(activation_time()).await;
+// A sporadic task starts waiting for its releases at its activation time,
+// those raised before are served right away
+pub async fn activation_sporadic(task: &TaskParameters) {
+    Mono::delay_until(activation_time(task)).await;
 }
 
-pub async fn activation_cyclic() {
-    Mono::delay_until(activation_time()).await;
-}
\ No newline at end of file
+// Release of a sporadic job arrived at `arrival` (cycle counter value), held
+// back until the minimum interarrival of the task has elapsed. Returns the
+// release instant, from which the deadline of the job is armed, and its cycle
+// counter value. None if the release is rejected.
+pub async fn sporadic_release(
+    deadline_timer: &mut impl rtic::Mutex<T = DeadlineTimer>,
+    deadline: DeadlineHandle,
+    arrival: u32,
+) -> Option<(Instant, u32)> {
+    let arrival_time = cycle_counter::instant(arrival);
+    match deadline_timer.lock(|timer| timer.object(deadline).admit(arrival_time)) {
+        Admission::Release(release_time) if release_time > arrival_time => {
+            Mono::delay_until(release_time).await;
+            Some((release_time, cycle_counter::at(release_time)))
+        }
+        Admission::Release(_) => Some((arrival_time, arrival)),
+        Admission::Rejected => None,
+    }
+}
@@ -0,0 +1,172 @@
+use core::fmt::Write;
+
+use crate::{
+    deadline::DeadlineTimer,
+    resources::{
+        activation_log::{ActivationLog, HISTORY_LENGTH},
+        event_queue::EventQueueSignaler,
+        request_buffer::RequestBuffer,
+    },
+    cycle_counter,
+    fault_injection,
+    stack,
+    time::Mono,
+};
+use rtic::Mutex;
+use rtic_monotonics::Monotonic;
+use rtic_sync::channel::{Receiver, Sender};
+use rtks_core::{
+    config::SystemConfig,
+    console::{self, Command, Line, LineBuffer, ParseError},
+    mode::{self, ModeChangeTrigger},
+    summary::RunSummary,
+    task_set::{CONSOLE_RX_PRIORITY, EXTERNAL_EVENT_SERVER, TASKS},
+    time,
+};
+use stm32f4xx_hal::{
+    pac::USART2,
+    prelude::*,
+    serial::{Rx, Tx},
+};
+
+// Lines received while the console is busy with a command
+pub const PENDING_LINES: usize = 2;
+
+const NO_FAULT_INJECTION: &str = "built without the fault-injection feature";
+
+pub type LineSender = Sender<'static, Line, PENDING_LINES>;
+pub type LineReceiver = Receiver<'static, Line, PENDING_LINES>;
+
+// Bound to the USART2 receive interrupt, hands complete lines to the console
+pub fn console_rx(rx: &mut Rx<USART2>, line_buffer: &mut LineBuffer, lines: &mut LineSender) {
+    // Reading the data register clears the interrupt
+    stack::sample(CONSOLE_RX_PRIORITY);
+    while let Ok(byte) = rx.read() {
+        if let Some(line) = line_buffer.push(byte) {
+            if lines.try_send(line).is_err() {
+                defmt::warn!("Console busy, command dropped.");
+            }
+        }
+    }
+}
+
+fn snapshot(
+    activation_log: &mut impl Mutex<T = ActivationLog>,
+    request_buffer: &mut impl Mutex<T = RequestBuffer>,
+    deadline_timer: &mut impl Mutex<T = DeadlineTimer>,
+) -> RunSummary<{ TASKS.len() }> {
+    RunSummary {
+        duration: time::to_millis(Mono::now().duration_since_epoch()),
+        deadlines: deadline_timer.lock(|timer| timer.statistics()),
+        activation_log: activation_log.lock(|al| al.read()),
+        request_buffer: request_buffer.lock(|buffer| buffer.statistics()),
+        stack: stack::usage(),
+    }
+}
+
+// CONSOLE
+// Runs in the background, the replies are written with blocking writes outside
+// of the locks
+pub async fn console(
+    lines: &mut LineReceiver,
+    tx: &mut Tx<USART2>,
+    system_config: &mut impl Mutex<T = SystemConfig>,
+    activation_log: &mut impl Mutex<T = ActivationLog>,
+    request_buffer: &mut impl Mutex<T = RequestBuffer>,
+    deadline_timer: &mut impl Mutex<T = DeadlineTimer>,
+    event_signaler: &mut EventQueueSignaler<'static>,
+) -> ! {
+    let _ = write!(tx, "rtks console, type 'help'\n> ");
+    loop {
+        let line = lines.recv().await.expect("Console receive interrupt gone");
+        let reply = match console::parse(line.as_str()) {
+            Ok(Command::Help) => console::write_help(tx),
+            Ok(Command::Stats) => console::write_stats(
+                tx,
+                &snapshot(activation_log, request_buffer, deadline_timer),
+                mode::current(),
+            ),
+            // Workloads of the current mode
+            Ok(Command::Tasks) => console::write_tasks(
+                tx,
+                &system_config.lock(|config| config.for_mode(mode::current())),
+            ),
+            Ok(Command::Deadlines) => console::write_deadlines(
+                tx,
+                &snapshot(activation_log, request_buffer, deadline_timer),
+            ),
+            Ok(Command::Log) => {
+                let mut entries = [None; HISTORY_LENGTH];
+                let interarrival = activation_log.lock(|al| {
+                    for (slot, entry) in entries.iter_mut().zip(al.entries_since(0)) {
+                        *slot = Some(entry);
+                    }
+                    al.interarrival()
+                });
+                console::write_log(tx, entries.iter().flatten(), interarrival)
+            }
+            Ok(Command::Config) => {
+                console::write_config(tx, &system_config.lock(|config| *config))
+            }
+            // Validated and installed under the lock, the tasks copy it at their next release
+            Ok(Command::Set(setting)) => {
+                let update = system_config
+                    .lock(|config| config.with(setting).inspect(|&new| *config = new));
+                match update {
+                    Ok(config) => {
+                        defmt::warn!("Console: system configuration changed: {}", config);
+                        console::write_config(tx, &config)
+                    }
+                    Err(err) => writeln!(tx, "rejected: {}", err),
+                }
+            }
+            Ok(Command::Mode) => console::write_mode(tx, mode::current(), mode::pending()),
+            Ok(Command::ChangeMode(target)) => {
+                mode::request(target, ModeChangeTrigger::Command);
+                defmt::info!("Mode change to {} requested by command.", target.name());
+                console::write_mode(tx, mode::current(), mode::pending())
+            }
+            // The event queue keeps the latest event only, and the server
+            // rejects the events arriving early
+            Ok(Command::TriggerEvent) => {
+                event_signaler.signal(cycle_counter::now());
+                defmt::info!("Console: external event signalled.");
+                writeln!(
+                    tx,
+                    "external event signalled, replaces a pending interrupt and is rejected within {} ms of the previous event",
+                    EXTERNAL_EVENT_SERVER.period()
+                )
+            }
+            // Faults apply to the jobs released from then on
+            Ok(Command::Faults) => match fault_injection::configure(|injector| *injector) {
+                Some(injector) => console::write_faults(tx, injector.windows()),
+                None => writeln!(tx, "{}", NO_FAULT_INJECTION),
+            },
+            Ok(Command::AddFault(window)) => {
+                match fault_injection::configure(|injector| injector.add(window)) {
+                    Some(true) => {
+                        defmt::warn!("Fault injection: {}", window);
+                        writeln!(tx, "fault added")
+                    }
+                    Some(false) => writeln!(tx, "no free fault slot, try 'fault clear'"),
+                    None => writeln!(tx, "{}", NO_FAULT_INJECTION),
+                }
+            }
+            Ok(Command::ClearFaults) => {
+                match fault_injection::configure(|injector| injector.clear()) {
+                    Some(()) => {
+                        defmt::warn!("Fault injection: cleared.");
+                        writeln!(tx, "faults cleared")
+                    }
+                    None => writeln!(tx, "{}", NO_FAULT_INJECTION),
+                }
+            }
+            Err(ParseError::Empty) => Ok(()),
+            Err(err) => writeln!(tx, "{}", err),
+        };
+        if reply.is_err() {
+            defmt::error!("Error writing console reply.");
+        }
+        let _ = write!(tx, "> ");
+    }
+}
@@ -0,0 +1,64 @@
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use cortex_m::{
+    asm,
+    peripheral::{DCB, DWT},
+};
+use rtic_monotonics::Monotonic;
+use rtks_core::{
+    time::{Duration, from_micros, to_micros},
+    timing::{cycles_to_micros, CYCLES_PER_MICROSECOND},
+};
+
+use crate::time::{Instant, Mono};
+
+// QEMU does not emulate the DWT cycle counter, which stays at 0: the cycle
+// count is then derived from the monotonic, at the resolution of its tick
+static STOPPED: AtomicBool = AtomicBool::new(false);
+
+// Start the DWT cycle counter used to timestamp jobs, after the monotonic
+pub fn init(dcb: &mut DCB, dwt: &mut DWT) {
+    dcb.enable_trace();
+    dwt.enable_cycle_counter();
+
+    let start = DWT::cycle_count();
+    asm::delay(1_000);
+    if DWT::cycle_count() == start {
+        STOPPED.store(true, Ordering::Relaxed);
+        defmt::warn!(
+            "Cycle counter stopped, job times are measured with the monotonic to {} cycles",
+            resolution()
+        );
+    }
+}
+
+// Cycles between two distinct values of `now`
+pub fn resolution() -> u32 {
+    if STOPPED.load(Ordering::Relaxed) {
+        to_micros(Duration::from_ticks(1)) * CYCLES_PER_MICROSECOND
+    } else {
+        1
+    }
+}
+
+pub fn now() -> u32 {
+    if STOPPED.load(Ordering::Relaxed) {
+        // Wraps like the cycle counter
+        let micros = Mono::now().duration_since_epoch().to_micros() as u32;
+        micros.wrapping_mul(CYCLES_PER_MICROSECOND)
+    } else {
+        DWT::cycle_count()
+    }
+}
+
+// Cycle counter value at a past `instant` of the monotonic, both run off the
+// core clock so the error is bounded by the monotonic tick
+pub fn at(instant: Instant) -> u32 {
+    let elapsed = to_micros(Mono::now() - instant);
+    now().wrapping_sub(elapsed.wrapping_mul(CYCLES_PER_MICROSECOND))
+}
+
+// Monotonic instant of a past cycle counter value, less than a counter period ago
+pub fn instant(cycles: u32) -> Instant {
+    Mono::now() - from_micros(cycles_to_micros(now().wrapping_sub(cycles)))
+}
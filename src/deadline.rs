@@ -1,90 +1,74 @@
-use crate::time::{Mono, Instant};
-use rtic_monotonics::{
-    Monotonic, 
-    fugit::ExtU32
+use crate::{stack, time::Mono};
+use rtic_monotonics::Monotonic;
+use rtic_sync::signal::{SignalReader, SignalWriter};
+use rtks_core::{
+    deadline::MissAction,
+    mode::{self, ModeChangeTrigger, SystemMode},
+    task_set::{DEADLINE_TIMER_PRIORITY, TASKS},
+    Notify,
 };
-use rtic_sync::signal::SignalReader;
 
-// SHARED RESOURCE FOR HANDLING DEADLINE
-pub struct DeadlineProtectedObject {
-    name: &'static str,
-    cancelled: bool,
-    misses: u32,
-    activations: u32,
-}
+pub use rtks_core::deadline::{DeadlineProtectedObject, Escalation, MissPolicy};
+pub use rtks_core::deadline_timer::{DeadlineHandle, JobId};
 
-impl DeadlineProtectedObject {
-    pub fn new(
-    name: &'static str,
-    ) -> Self {
-        return DeadlineProtectedObject {
-            name,
-            cancelled: false, 
-            misses: 0,
-            activations: 1
-        };
-    }
+// Wakes the deadline timer service when a deadline earlier than the one it is
+// sleeping on is armed
+pub struct DeadlineWaker {
+    inner: SignalWriter<'static, ()>,
+}
 
-    pub fn deadline_miss_detected(&mut self) {
-        if !self.cancelled {
-            self.misses += 1;
-            defmt::error!(
-                "Deadline miss detected for task '{}'. Misses: {}, Activations: {}",
-                self.name,
-                self.misses,
-                self.activations
-            );
-        } else {
-            defmt::info!(
-                "Deadline for task '{}' was cancelled",
-                self.name
-            );
-        }
-        // reset deadline object
-        self.cancelled = false;
-        self.activations += 1;
+impl DeadlineWaker {
+    pub fn new(inner: SignalWriter<'static, ()>) -> Self {
+        DeadlineWaker { inner }
     }
+}
 
-    pub fn cancel_deadline(&mut self, activation_id: u32) {
-        if activation_id == self.activations {
-            self.cancelled = true;
-        } 
-        // else ignore it, too late to cancel
+impl Notify for DeadlineWaker {
+    fn notify(&mut self) {
+        self.inner.write(());
     }
 }
 
-// DEADLINE MISS HANDLERS TASKS
-pub async fn periodic_deadline_watchdog(
-    deadline_protected_object: &mut impl rtic::Mutex<T = DeadlineProtectedObject>,
-    next_deadline: &mut Instant, 
-    period: u32,
-) -> ! {
-    // Watchdog control loop
-    loop {
-        Mono::delay_until(*next_deadline).await;
-
-        deadline_protected_object.lock(|dpo| {
-            dpo.deadline_miss_detected();
-        });
+// Room for two pending jobs per task
+pub type DeadlineTimer =
+    rtks_core::deadline_timer::DeadlineTimer<DeadlineWaker, { TASKS.len() }, { 2 * TASKS.len() }>;
 
-        *next_deadline += period.millis();
+// Carry out the system-level part of the miss policy, outside of the lock
+fn handle_miss(action: MissAction) {
+    match action {
+        MissAction::None => {}
+        MissAction::EnterDegradedMode => {
+            let requested = mode::pending().map_or(mode::current(), |request| request.mode);
+            if requested != SystemMode::Degraded {
+                defmt::warn!("Entering degraded mode.");
+                mode::request(SystemMode::Degraded, ModeChangeTrigger::DeadlineMiss);
+            }
+        }
+        MissAction::Panic => defmt::panic!("Too many consecutive deadline misses."),
+        MissAction::Reset => cortex_m::peripheral::SCB::sys_reset(),
     }
 }
 
-pub async fn sporadic_deadline_watchdog(
-    deadline_protected_object: &mut impl rtic::Mutex<T = DeadlineProtectedObject>,
-    activation_reader: &mut SignalReader<'static, Instant>,
-    next_deadline: &mut Instant, 
-    deadline: u32,
+// DEADLINE TIMER SERVICE
+pub async fn deadline_timer_service(
+    deadline_timer: &mut impl rtic::Mutex<T = DeadlineTimer>,
+    waker: &mut SignalReader<'static, ()>,
 ) -> ! {
-    // Watchdog control loop
+    // Service control loop
     loop {
-        *next_deadline = activation_reader.wait().await + deadline.millis();
-        
-        Mono::delay_until(*next_deadline).await;
+        // Sleep until the earliest deadline, or until an earlier one is armed
+        match deadline_timer.lock(|timer| timer.next_expiry()) {
+            Some(expiry) => {
+                let _ = Mono::timeout_at(expiry, waker.wait()).await;
+            }
+            None => waker.wait().await,
+        }
 
-        deadline_protected_object.lock(|dpo| {
-            dpo.deadline_miss_detected();
-        });
+        while let Some(action) = deadline_timer.lock(|timer| {
+            stack::sample(DEADLINE_TIMER_PRIORITY);
+            timer.expire(Mono::now())
+        }) {
+            handle_miss(action);
+        }
     }
-}
\ No newline at end of file
+}
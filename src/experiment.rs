@@ -0,0 +1,57 @@
+use crate::{
+    deadline::DeadlineTimer,
+    resources::{activation_log::ActivationLog, request_buffer::RequestBuffer},
+    stack,
+    time::Mono,
+};
+use cortex_m::asm::nop;
+use cortex_m_semihosting::debug::{self, EXIT_FAILURE, EXIT_SUCCESS};
+use rtic::Mutex;
+use rtic_monotonics::{Monotonic, fugit::ExtU32};
+use rtks_core::{mode, summary::RunSummary};
+
+// Length of a bounded run (milliseconds), override at build time with RTKS_RUN_DURATION_MS
+pub const RUN_DURATION: u32 = match option_env!("RTKS_RUN_DURATION_MS") {
+    Some(duration) => parse_millis(duration),
+    None => 10_000,
+};
+
+const fn parse_millis(duration: &str) -> u32 {
+    let digits = duration.as_bytes();
+    assert!(!digits.is_empty(), "RTKS_RUN_DURATION_MS is empty");
+    let mut millis: u32 = 0;
+    let mut i = 0;
+    while i < digits.len() {
+        assert!(digits[i].is_ascii_digit(), "RTKS_RUN_DURATION_MS is not a number");
+        millis = millis * 10 + (digits[i] - b'0') as u32;
+        i += 1;
+    }
+    millis
+}
+
+// Ends the experiment once RUN_DURATION has elapsed since boot, reporting the
+// state of the system and failing the run if any deadline was missed
+pub async fn supervisor(
+    activation_log: &mut impl Mutex<T = ActivationLog>,
+    request_buffer: &mut impl Mutex<T = RequestBuffer>,
+    deadline_timer: &mut impl Mutex<T = DeadlineTimer>,
+) -> ! {
+    Mono::delay_until(Mono::now() + RUN_DURATION.millis()).await;
+
+    defmt::info!("End of run.");
+    let summary = RunSummary {
+        duration: RUN_DURATION,
+        deadlines: deadline_timer.lock(|timer| timer.statistics()),
+        activation_log: activation_log.lock(|al| al.read()),
+        request_buffer: request_buffer.lock(|buffer| buffer.statistics()),
+        stack: stack::usage(),
+    };
+    summary.log();
+    defmt::info!("Mode: {} after {} mode changes", mode::current().name(), mode::changes());
+
+    debug::exit(if summary.passed() { EXIT_SUCCESS } else { EXIT_FAILURE });
+
+    loop {
+        nop();
+    }
+}
@@ -0,0 +1,124 @@
+// Fault injection hooks called by the tasks. They do nothing unless the
+// firmware is built with the `fault-injection` feature, in which case they
+// follow the fault windows of a `FaultInjector`, loaded with SCENARIO in init
+// and reconfigurable at run time from the console with `configure`.
+
+#[cfg(feature = "fault-injection")]
+mod injector {
+    use core::{
+        cell::RefCell,
+        sync::atomic::{AtomicU32, Ordering},
+    };
+    use cortex_m::interrupt::{self, Mutex};
+    use rtic_monotonics::{Monotonic, fugit::ExtU32};
+    use rtks_core::{
+        fault_injection::{Fault, FaultInjector, FaultWindow},
+        task_set::{
+            ACTIVATION_LOG_READER, EXTERNAL_EVENT_SERVER, ON_CALL_PRODUCER, REGULAR_PRODUCER,
+            TaskParameters,
+        },
+    };
+
+    use crate::time::Mono;
+
+    // Faults injected from boot, all within the first 10 s of a run
+    const SCENARIO: [FaultWindow; 4] = [
+        // A completed job whose deadline is not cancelled is a miss
+        FaultWindow {
+            task: REGULAR_PRODUCER.name,
+            first_job: 3,
+            last_job: 3,
+            fault: Fault::SuppressCancellation,
+        },
+        // Exceeds the budget long before the deadline
+        FaultWindow {
+            task: ACTIVATION_LOG_READER.name,
+            first_job: 2,
+            last_job: 2,
+            fault: Fault::InflateWorkload { factor: 100 },
+        },
+        FaultWindow {
+            task: ON_CALL_PRODUCER.name,
+            first_job: 1,
+            last_job: 5,
+            fault: Fault::ReleaseJitter { max: 50 },
+        },
+        // The 5 interrupts following the first one are rejected
+        FaultWindow {
+            task: EXTERNAL_EVENT_SERVER.name,
+            first_job: 1,
+            last_job: 5,
+            fault: Fault::FloodEvents { interarrival: 10 },
+        },
+    ];
+
+    static INJECTOR: Mutex<RefCell<FaultInjector>> = Mutex::new(RefCell::new(FaultInjector::new()));
+    static EXTERNAL_INTERRUPTS: AtomicU32 = AtomicU32::new(0);
+
+    pub fn init() {
+        configure(|injector| {
+            injector.clear();
+            for window in SCENARIO {
+                injector.add(window);
+            }
+        });
+        for window in SCENARIO {
+            defmt::warn!("Fault injection: {}", window);
+        }
+    }
+
+    // None when the firmware is built without fault injection
+    pub fn configure<R>(update: impl FnOnce(&mut FaultInjector) -> R) -> Option<R> {
+        Some(interrupt::free(|cs| update(&mut INJECTOR.borrow(cs).borrow_mut())))
+    }
+
+    fn with<R>(query: impl FnOnce(&FaultInjector) -> R) -> R {
+        interrupt::free(|cs| query(&INJECTOR.borrow(cs).borrow()))
+    }
+
+    pub fn workload_factor(task: &TaskParameters, job: u32) -> u32 {
+        with(|injector| injector.workload_factor(task.name, job))
+    }
+
+    pub async fn delay_release(task: &TaskParameters, job: u32) {
+        let delay = with(|injector| injector.release_delay(task.name, job));
+        if delay > 0 {
+            Mono::delay_until(Mono::now() + delay.millis()).await;
+        }
+    }
+
+    pub fn cancellation_suppressed(task: &TaskParameters, job: u32) -> bool {
+        with(|injector| injector.cancellation_suppressed(task.name, job))
+    }
+
+    // Called by the external interrupt each time it is raised
+    pub fn event_interarrival(task: &TaskParameters) -> Option<u32> {
+        let interrupt = EXTERNAL_INTERRUPTS.fetch_add(1, Ordering::Relaxed) + 1;
+        with(|injector| injector.event_interarrival(task.name, interrupt))
+    }
+}
+
+#[cfg(not(feature = "fault-injection"))]
+mod injector {
+    use rtks_core::{fault_injection::FaultInjector, task_set::TaskParameters};
+
+    pub fn configure<R>(_update: impl FnOnce(&mut FaultInjector) -> R) -> Option<R> {
+        None
+    }
+
+    pub fn workload_factor(_task: &TaskParameters, _job: u32) -> u32 {
+        1
+    }
+
+    pub async fn delay_release(_task: &TaskParameters, _job: u32) {}
+
+    pub fn cancellation_suppressed(_task: &TaskParameters, _job: u32) -> bool {
+        false
+    }
+
+    pub fn event_interarrival(_task: &TaskParameters) -> Option<u32> {
+        None
+    }
+}
+
+pub use injector::*;
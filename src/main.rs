@@ -1,11 +1,16 @@
 #![no_std]
 #![no_main]
 
-mod auxiliary;
 mod activation_manager;
+mod console;
+mod cycle_counter;
 mod deadline;
-mod production_workload;
+#[cfg(feature = "bounded-run")]
+mod experiment;
+mod fault_injection;
+mod mode_change;
 mod resources;
+mod stack;
 mod tasks;
 mod time;
 
@@ -26,28 +31,49 @@ fn panic(info: &core::panic::PanicInfo) -> ! {
 
 #[rtic::app(
     device = stm32f4xx_hal::pac,
-    dispatchers = [EXTI0, EXTI1, EXTI2, EXTI3, EXTI4])]
+    dispatchers = [EXTI0, EXTI1, EXTI2, EXTI3, EXTI4, EXTI9_5])]
 mod app {
 
     use crate::{
         activation_manager,
+        console::{LineReceiver, LineSender, PENDING_LINES},
         deadline::{
+            DeadlineHandle,
             DeadlineProtectedObject, 
-            periodic_deadline_watchdog,
-            sporadic_deadline_watchdog},
+            DeadlineTimer,
+            DeadlineWaker,
+            Escalation,
+            MissPolicy,
+            deadline_timer_service},
         resources::{
             activation_log::ActivationLog,
             event_queue::{EventQueue, EventQueueSignaler, EventQueueWaiter},
-            request_buffer::RequestBuffer,
+            request_buffer::{Barrier, RequestBuffer},
             task_semaphore::{TaskSemaphore, TaskSemaphoreSignaler, TaskSemaphoreWaiter},
         },
+        cycle_counter,
         tasks,
         time::{Mono, Instant},
     };
     use cortex_m::asm::nop;
+    use rtks_core::{
+        budget::{self, ExecutionBudget},
+        config::SystemConfig,
+        console::{Line, LineBuffer},
+        interarrival::{EarlyRelease, InterarrivalGuard},
+        production_workload::{self, Calibration},
+        task_set,
+    };
     use rtic_monotonics::{fugit::RateExtU32 as _, systick::prelude::*};
-    use rtic_sync::{make_signal, signal::SignalReader, signal::SignalWriter};
-    use stm32f4xx_hal::rcc::RccExt;
+    use rtic_sync::{make_channel, make_signal, signal::SignalReader};
+    use stm32f4xx_hal::{
+        gpio::GpioExt,
+        pac::{TIM5, USART2},
+        rcc::RccExt,
+        serial::{self, Rx, Serial, Tx},
+        time::Bps,
+        timer::{CounterUs, Event, TimerExt},
+    };
 
     // Shared resources go here
     #[shared]
@@ -55,76 +81,99 @@ mod app {
         activation_log: ActivationLog,
         request_buffer: RequestBuffer,
 
-        activation_log_reader_deadline_protected_object: DeadlineProtectedObject,
-        external_event_server_deadline_protected_object: DeadlineProtectedObject,
-        on_call_producer_deadline_protected_object: DeadlineProtectedObject,
-        regular_producer_deadline_protected_object: DeadlineProtectedObject,
+        deadline_timer: DeadlineTimer,
+        system_config: SystemConfig,
     }
 
     // Local resources go here
     #[local]
     struct Local {
+        // External_Interrupt
         event_signaler: EventQueueSignaler<'static>,
+        external_interrupt_timer: CounterUs<TIM5>,
+        external_interrupt_seed: u32,
         // External_Event_Server
         event_waiter: EventQueueWaiter<'static>,
-        external_event_server_activation_writer: SignalWriter<'static, Instant>,
-        external_event_server_activation_count: u32,
+        external_event_server_deadline: DeadlineHandle,
+        external_event_server_budget: ExecutionBudget,
         // Activation_Log_Reader
         activation_log_reader_waiter: TaskSemaphoreWaiter<'static>,
-        activation_log_reader_activation_writer: SignalWriter<'static, Instant>,
-        activation_log_reader_activation_count: u32,
+        activation_log_reader_deadline: DeadlineHandle,
+        activation_log_reader_budget: ExecutionBudget,
         // On_Call_Producer
         current_workload: u32,
         barrier_reader: SignalReader<'static, ()>,
-        on_call_producer_activation_writer: SignalWriter<'static, Instant>,
-        on_call_producer_activation_count: u32,
+        on_call_producer_deadline: DeadlineHandle,
+        on_call_producer_budget: ExecutionBudget,
         // Regular_Producer
         activation_log_reader_signaler: TaskSemaphoreSignaler<'static>,
         regular_producer_next_time: Instant,
-        regular_producer_activation_count: u32,
-        // Activation_Log_Reader_Deadline_Miss_Handler
-        activation_log_reader_activation_reader: SignalReader<'static, Instant>,
-        activation_log_reader_deadline_value: u32,
-        activation_log_reader_next_deadline: Instant,
-        // External_Event_Server_Deadline_Miss_Handler
-        external_event_server_activation_reader: SignalReader<'static, Instant>,
-        external_event_server_deadline_value: u32,
-        external_event_server_next_deadline: Instant,
-        // On_Call_Producer_Deadline_Miss_Handler
-        on_call_producer_activation_reader: SignalReader<'static, Instant>,
-        on_call_producer_deadline_value: u32,
-        on_call_producer_next_deadline: Instant, 
-        // Regular_Producer_Deadline_Miss_Handler
-        regular_producer_period: u32,
-        regular_producer_next_deadline: Instant,
+        regular_producer_deadline: DeadlineHandle,
+        regular_producer_budget: ExecutionBudget,
+        // Deadline_Timer
+        deadline_waker: SignalReader<'static, ()>,
+        // Console_Rx
+        console_serial_rx: Rx<USART2>,
+        console_line_buffer: LineBuffer,
+        console_line_sender: LineSender,
+        // Console
+        console_serial_tx: Tx<USART2>,
+        console_line_receiver: LineReceiver,
+        console_event_signaler: EventQueueSignaler<'static>,
     }
 
     #[init(local = [
         activation_log_reader_semaphore: TaskSemaphore = TaskSemaphore::new(),
     ])]
     fn init(cx: init::Context) -> (Shared, Local) {
+        // Paint the stack before anything else runs on it
+        crate::stack::paint();
         defmt::info!("Init");
 
         // Extract device from context
         let peripherals = cx.device;
-        let core = cx.core;
+        let mut core = cx.core;
 
         // Clocks setup
         let rcc = peripherals.RCC.constrain();
         let clocks = rcc
             .cfgr
             .use_hse(8.MHz())
-            .sysclk(168.MHz())
+            .sysclk(rtks_core::time::SYSCLK_HZ.Hz())
             .pclk1(42.MHz())
             .freeze();
 
         defmt::info!("Clocks initialized");
 
         // Setup monotonic timer
+        #[cfg(not(feature = "tim2-monotonic"))]
         Mono::start(core.SYST, clocks.sysclk().to_Hz());
+        #[cfg(feature = "tim2-monotonic")]
+        Mono::start(clocks.timclk1().to_Hz());
+        // Setup cycle counter for job timing
+        cycle_counter::init(&mut core.DCB, &mut core.DWT);
+
+        // Setup the system configuration, the task set is analysed with the default one
+        let system_config = SystemConfig::DEFAULT;
+        if let Err(err) = system_config.validate() {
+            defmt::panic!("Invalid system configuration: {}", err);
+        }
+        defmt::info!("System configuration: {}", system_config);
+
+        // Load the fault injection scenario
+        #[cfg(feature = "fault-injection")]
+        crate::fault_injection::init();
 
         // Setup event queue
         let (event_waiter, event_signaler) = EventQueue::init();
+        let console_event_signaler = event_signaler.clone();
+        // Setup external interrupt source
+        let mut external_interrupt_seed = tasks::external_interrupt::JITTER_SEED;
+        let mut external_interrupt_timer = peripherals.TIM5.counter_us(&clocks);
+        external_interrupt_timer
+            .start(tasks::external_interrupt::first_interarrival(&mut external_interrupt_seed).millis())
+            .expect("Error starting external interrupt timer");
+        external_interrupt_timer.listen(Event::Update);
         // Setup activation log
         let activation_log = ActivationLog::new();
         // Setup activation log reader semaphore
@@ -133,74 +182,151 @@ mod app {
         // Setup barrier for on call producer
         let (barrier_writer, barrier_reader) = make_signal!(());
         // Setup request buffer
-        let request_buffer = RequestBuffer::new(barrier_writer);
-        // Setup external event server deadline
-        let external_event_server_deadline_protected_object = DeadlineProtectedObject::new("External_Event_Server");
-        let (external_event_server_activation_writer, external_event_server_activation_reader) = make_signal!(Instant);
-        // Setup activation log reader deadline
-        let activation_log_reader_deadline_protected_object = DeadlineProtectedObject::new("Activation_Log_Reader");
-        let (activation_log_reader_activation_writer, activation_log_reader_activation_reader) = make_signal!(Instant);
-        // Setup on call producer deadline
-        let on_call_producer_deadline_protected_object = DeadlineProtectedObject::new("On_Call_Producer");
-        let (on_call_producer_activation_writer, on_call_producer_activation_reader) = make_signal!(Instant);
-        // Setup regular producer deadline
-        let regular_producer_deadline_protected_object = DeadlineProtectedObject::new("Regular_Producer");
-
-        activation_log_reader_deadline_miss_handler::spawn().expect("Error spawning activation log reader deadline miss handler");
-        external_event_server_deadline_miss_handler::spawn().expect("Error spawning external event server deadline miss handler");
-        on_call_producer_deadline_miss_handler::spawn().expect("Error spawning on call producer deadline miss handler");
-        regular_producer_deadline_miss_handler::spawn().expect("Error spawning regular producer deadline miss handler");
+        let request_buffer = RequestBuffer::new(Barrier::new(barrier_writer));
+        // Setup deadlines, each task gets the miss policy under evaluation and
+        // sporadic tasks the minimum interarrival of the task set
+        let (deadline_waker_writer, deadline_waker) = make_signal!(());
+        let deadline_timer = DeadlineTimer::new(
+            [
+                DeadlineProtectedObject::new(
+                    task_set::EXTERNAL_EVENT_SERVER.name,
+                    MissPolicy::Escalate { consecutive_misses: 3, escalation: Escalation::Panic },
+                )
+                .with_interarrival(InterarrivalGuard::new(
+                    &task_set::EXTERNAL_EVENT_SERVER,
+                    EarlyRelease::Reject,
+                )),
+                DeadlineProtectedObject::new(
+                    task_set::ACTIVATION_LOG_READER.name,
+                    MissPolicy::AbortJob,
+                )
+                .with_interarrival(InterarrivalGuard::new(
+                    &task_set::ACTIVATION_LOG_READER,
+                    EarlyRelease::Defer,
+                )),
+                DeadlineProtectedObject::new(
+                    task_set::ON_CALL_PRODUCER.name,
+                    MissPolicy::SkipNextRelease,
+                )
+                .with_interarrival(InterarrivalGuard::new(
+                    &task_set::ON_CALL_PRODUCER,
+                    EarlyRelease::Defer,
+                )),
+                DeadlineProtectedObject::new(
+                    task_set::REGULAR_PRODUCER.name,
+                    MissPolicy::DegradedMode,
+                ),
+            ],
+            DeadlineWaker::new(deadline_waker_writer),
+        );
+        let [
+            external_event_server_deadline,
+            activation_log_reader_deadline,
+            on_call_producer_deadline,
+            regular_producer_deadline,
+        ] = DeadlineTimer::handles();
+        // Setup execution budgets, each with the overrun handler under evaluation and
+        // the resolution of the cycle counter
+        let external_event_server_budget = ExecutionBudget::new(
+            &task_set::EXTERNAL_EVENT_SERVER,
+            budget::log_overrun,
+            &budget::INTERFERENCE,
+        )
+        .with_resolution(cycle_counter::resolution());
+        let activation_log_reader_budget = ExecutionBudget::new(
+            &task_set::ACTIVATION_LOG_READER,
+            budget::abort_on_overrun,
+            &budget::INTERFERENCE,
+        )
+        .with_resolution(cycle_counter::resolution());
+        let on_call_producer_budget = ExecutionBudget::new(
+            &task_set::ON_CALL_PRODUCER,
+            budget::log_overrun,
+            &budget::INTERFERENCE,
+        )
+        .with_resolution(cycle_counter::resolution());
+        let regular_producer_budget = ExecutionBudget::new(
+            &task_set::REGULAR_PRODUCER,
+            budget::log_overrun,
+            &budget::INTERFERENCE,
+        )
+        .with_resolution(cycle_counter::resolution());
+
+        // Setup console on USART2, lines are handed from the receive interrupt
+        // to the console task
+        let gpioa = peripherals.GPIOA.split();
+        let mut console_serial = Serial::new(
+            peripherals.USART2,
+            (gpioa.pa2, gpioa.pa3),
+            serial::Config::default().baudrate(Bps(task_set::CONSOLE_BAUD_RATE)),
+            &clocks,
+        )
+        .expect("Error configuring console serial port");
+        console_serial.listen(serial::Event::RxNotEmpty);
+        let (console_serial_tx, console_serial_rx) = console_serial.split();
+        let (console_line_sender, console_line_receiver) = make_channel!(Line, PENDING_LINES);
+
+        // Fix the common start epoch of the task set
+        let epoch = activation_manager::init();
+
+        // The workloads are calibrated before the epoch, once the monotonic runs
+        calibration::spawn().expect("Error spawning calibration task");
+        deadline_timer::spawn().expect("Error spawning deadline timer service");
 
         external_event_server::spawn().expect("Error spawning external event server");
         activation_log_reader::spawn().expect("Error spawning activation log reader task");
         regular_producer::spawn().expect("Error spawning regular producer task");
         on_call_producer::spawn().expect("Error spawning on call producer task");
+        console::spawn().expect("Error spawning console task");
+        stack_monitor::spawn().expect("Error spawning stack monitor task");
+
+        #[cfg(feature = "bounded-run")]
+        supervisor::spawn().expect("Error spawning supervisor task");
+        #[cfg(feature = "mode-schedule")]
+        mode_scheduler::spawn().expect("Error spawning mode scheduler task");
 
         (
             Shared {
                 // Initialization of shared resources go here
                 request_buffer,
                 activation_log,
-                activation_log_reader_deadline_protected_object,
-                external_event_server_deadline_protected_object,
-                on_call_producer_deadline_protected_object,
-                regular_producer_deadline_protected_object,
+                deadline_timer,
+                system_config,
             },
             Local {
                 // Initialization of local resources go here
+                // External_Interrupt
                 event_signaler,
+                external_interrupt_timer,
+                external_interrupt_seed,
                 // External_Event_Server
                 event_waiter,
-                external_event_server_activation_writer,
-                external_event_server_activation_count: 0,
+                external_event_server_deadline,
+                external_event_server_budget,
                 // Activation_Log_Reader
                 activation_log_reader_signaler,
                 activation_log_reader_waiter,
-                activation_log_reader_activation_writer,
-                activation_log_reader_activation_count: 0,
+                activation_log_reader_deadline,
+                activation_log_reader_budget,
                 // On_Call_Producer
                 current_workload: 0,
                 barrier_reader,
-                on_call_producer_activation_writer,
-                on_call_producer_activation_count: 0,
+                on_call_producer_deadline,
+                on_call_producer_budget,
                 // Regular_Producer
-                regular_producer_next_time: activation_manager::activation_time(),
-                regular_producer_activation_count: 0,
-                // Activation_Log_Reader_Deadline_Miss_Handler
-                activation_log_reader_activation_reader,
-                activation_log_reader_deadline_value: tasks::activation_log_reader::DEADLINE,
-                activation_log_reader_next_deadline: Instant::from_ticks(0),
-                // External_Event_Server_Deadline_Miss_Handler
-                external_event_server_activation_reader,
-                external_event_server_deadline_value: tasks::external_event_server::DEADLINE,
-                external_event_server_next_deadline: Instant::from_ticks(0),
-                // On_Call_Producer_Deadline_Miss_Handler
-                on_call_producer_activation_reader,
-                on_call_producer_deadline_value: tasks::on_call_producer_task::DEADLINE,
-                on_call_producer_next_deadline: Instant::from_ticks(0),
-                // Regular_Producer_Deadline_Miss_Handler
-                regular_producer_period: tasks::regular_producer_task::PERIOD,
-                regular_producer_next_deadline: activation_manager::activation_time() + tasks::regular_producer_task::DEADLINE.millis(), 
+                regular_producer_next_time: epoch,
+                regular_producer_deadline,
+                regular_producer_budget,
+                // Deadline_Timer
+                deadline_waker,
+                // Console_Rx
+                console_serial_rx,
+                console_line_buffer: LineBuffer::new(),
+                console_line_sender,
+                // Console
+                console_serial_tx,
+                console_line_receiver,
+                console_event_signaler,
             },
         )
     }
@@ -212,91 +338,131 @@ mod app {
         }
     }
 
-    #[task(priority = 3, local=[activation_log_reader_waiter, activation_log_reader_activation_writer, activation_log_reader_activation_count], shared=[activation_log, activation_log_reader_deadline_protected_object])]
+    #[cfg(feature = "bounded-run")]
+    #[task(priority = 1, shared = [activation_log, request_buffer, deadline_timer])]
+    async fn supervisor(mut cx: supervisor::Context) -> ! {
+        crate::experiment::supervisor(
+            &mut cx.shared.activation_log,
+            &mut cx.shared.request_buffer,
+            &mut cx.shared.deadline_timer,
+        )
+        .await;
+    }
+
+    #[cfg(feature = "mode-schedule")]
+    #[task(priority = 1)]
+    async fn mode_scheduler(_: mode_scheduler::Context) {
+        crate::mode_change::mode_scheduler().await;
+    }
+
+    // Calibrate the workloads on the current clock. Runs once, below the
+    // monotonic interrupt so that the fallback of the cycle counter advances,
+    // and completes well within the offset of the epoch
+    #[task(priority = 1)]
+    async fn calibration(_: calibration::Context) {
+        match production_workload::calibrate(cycle_counter::now) {
+            Ok(Calibration::Measured(cycles)) => {
+                defmt::info!("Workload calibrated: {} cycles per kilo-whetstone", cycles)
+            }
+            Ok(Calibration::Unmeasured(cycles)) => defmt::warn!(
+                "Workload not calibrated, the cycle count did not advance. Assuming {} cycles per kilo-whetstone",
+                cycles
+            ),
+            Err(err) => defmt::error!("Error calibrating workload: {}", err),
+        }
+    }
+
+    #[task(priority = 1)]
+    async fn stack_monitor(_: stack_monitor::Context) -> ! {
+        crate::stack::stack_monitor().await;
+    }
+
+    #[task(binds = TIM5, priority = 13, local = [external_interrupt_timer, event_signaler, external_interrupt_seed])]
+    fn external_interrupt(cx: external_interrupt::Context) {
+        tasks::external_interrupt::external_interrupt(
+            cx.local.external_interrupt_timer,
+            cx.local.event_signaler,
+            cx.local.external_interrupt_seed,
+        );
+    }
+
+    #[task(priority = 3, local=[activation_log_reader_waiter, activation_log_reader_deadline, activation_log_reader_budget], shared=[system_config, activation_log, deadline_timer])]
     async fn activation_log_reader(mut cx: activation_log_reader::Context) -> ! {
         tasks::activation_log_reader::activation_log_reader(
             cx.local.activation_log_reader_waiter,
+            &mut cx.shared.system_config,
             &mut cx.shared.activation_log,
-            &mut cx.local.activation_log_reader_activation_writer,
-            &mut cx.shared.activation_log_reader_deadline_protected_object,
-            &mut cx.local.activation_log_reader_activation_count,
+            &mut cx.shared.deadline_timer,
+            *cx.local.activation_log_reader_deadline,
+            cx.local.activation_log_reader_budget,
         )
         .await;
     }
 
-    #[task(priority = 11, local=[event_waiter, external_event_server_activation_writer, external_event_server_activation_count], shared=[activation_log, external_event_server_deadline_protected_object])]
+    #[task(priority = 11, local=[event_waiter, external_event_server_deadline, external_event_server_budget], shared=[activation_log, deadline_timer])]
     async fn external_event_server(mut cx: external_event_server::Context) -> ! {
         tasks::external_event_server::external_event_server(
             cx.local.event_waiter,
             &mut cx.shared.activation_log,
-            &mut cx.local.external_event_server_activation_writer,
-            &mut cx.shared.external_event_server_deadline_protected_object,
-            &mut cx.local.external_event_server_activation_count,
+            &mut cx.shared.deadline_timer,
+            *cx.local.external_event_server_deadline,
+            cx.local.external_event_server_budget,
         )
         .await;
     }
 
-    #[task(priority = 5, local = [current_workload, barrier_reader, on_call_producer_activation_writer, on_call_producer_activation_count], shared =[request_buffer, on_call_producer_deadline_protected_object])]
+    #[task(priority = 5, local = [current_workload, barrier_reader, on_call_producer_deadline, on_call_producer_budget], shared =[request_buffer, deadline_timer])]
     async fn on_call_producer(mut cx: on_call_producer::Context) {
         tasks::on_call_producer_task::on_call_producer_task(
             &mut cx.shared.request_buffer,
             cx.local.current_workload,
             cx.local.barrier_reader,
-            &mut cx.local.on_call_producer_activation_writer,
-            &mut cx.shared.on_call_producer_deadline_protected_object,
-            &mut cx.local.on_call_producer_activation_count
+            &mut cx.shared.deadline_timer,
+            *cx.local.on_call_producer_deadline,
+            cx.local.on_call_producer_budget,
         )
         .await;
     }
 
-    #[task(priority = 7, local = [regular_producer_next_time, activation_log_reader_signaler, regular_producer_activation_count], shared = [request_buffer, regular_producer_deadline_protected_object])]
+    #[task(priority = 7, local = [regular_producer_next_time, activation_log_reader_signaler, regular_producer_deadline, regular_producer_budget], shared = [system_config, request_buffer, deadline_timer])]
     async fn regular_producer(mut cx: regular_producer::Context) {
         tasks::regular_producer_task::regular_producer_task(
             cx.local.regular_producer_next_time,
+            &mut cx.shared.system_config,
             &mut cx.shared.request_buffer,
             cx.local.activation_log_reader_signaler,
-            &mut cx.shared.regular_producer_deadline_protected_object,
-            &mut cx.local.regular_producer_activation_count
+            &mut cx.shared.deadline_timer,
+            *cx.local.regular_producer_deadline,
+            cx.local.regular_producer_budget,
         )
         .await;
     }
 
-    #[task(priority = 12, local = [activation_log_reader_activation_reader, activation_log_reader_next_deadline, activation_log_reader_deadline_value], shared =[activation_log_reader_deadline_protected_object])]
-    async fn activation_log_reader_deadline_miss_handler(mut cx: activation_log_reader_deadline_miss_handler::Context) -> ! {
-        sporadic_deadline_watchdog(
-            &mut cx.shared.activation_log_reader_deadline_protected_object,
-            &mut cx.local.activation_log_reader_activation_reader,
-            &mut cx.local.activation_log_reader_next_deadline,
-            *cx.local.activation_log_reader_deadline_value,
-        ).await;
-    }
-
-    #[task(priority = 12, local = [external_event_server_activation_reader, external_event_server_next_deadline, external_event_server_deadline_value], shared =[external_event_server_deadline_protected_object])]
-    async fn external_event_server_deadline_miss_handler(mut cx: external_event_server_deadline_miss_handler::Context) -> ! {
-        sporadic_deadline_watchdog(
-            &mut cx.shared.external_event_server_deadline_protected_object,
-            &mut cx.local.external_event_server_activation_reader,
-            &mut cx.local.external_event_server_next_deadline,
-            *cx.local.external_event_server_deadline_value,
-        ).await;
+    #[task(priority = 12, local = [deadline_waker], shared = [deadline_timer])]
+    async fn deadline_timer(mut cx: deadline_timer::Context) -> ! {
+        deadline_timer_service(&mut cx.shared.deadline_timer, cx.local.deadline_waker).await;
     }
 
-    #[task(priority = 12, local = [on_call_producer_activation_reader, on_call_producer_next_deadline, on_call_producer_deadline_value], shared =[on_call_producer_deadline_protected_object])]
-    async fn on_call_producer_deadline_miss_handler(mut cx: on_call_producer_deadline_miss_handler::Context) -> ! {
-        sporadic_deadline_watchdog(
-            &mut cx.shared.on_call_producer_deadline_protected_object,
-            &mut cx.local.on_call_producer_activation_reader,
-            &mut cx.local.on_call_producer_next_deadline,
-            *cx.local.on_call_producer_deadline_value,
-        ).await;
+    #[task(binds = USART2, priority = 14, local = [console_serial_rx, console_line_buffer, console_line_sender])]
+    fn console_rx(cx: console_rx::Context) {
+        crate::console::console_rx(
+            cx.local.console_serial_rx,
+            cx.local.console_line_buffer,
+            cx.local.console_line_sender,
+        );
     }
 
-    #[task(priority = 12, local = [regular_producer_next_deadline, regular_producer_period], shared =[regular_producer_deadline_protected_object])]
-    async fn regular_producer_deadline_miss_handler(mut cx: regular_producer_deadline_miss_handler::Context) -> ! {
-        periodic_deadline_watchdog(
-            &mut cx.shared.regular_producer_deadline_protected_object,
-            &mut cx.local.regular_producer_next_deadline,
-            *cx.local.regular_producer_period,
-        ).await;
+    #[task(priority = 1, local = [console_line_receiver, console_serial_tx, console_event_signaler], shared = [system_config, activation_log, request_buffer, deadline_timer])]
+    async fn console(mut cx: console::Context) -> ! {
+        crate::console::console(
+            cx.local.console_line_receiver,
+            cx.local.console_serial_tx,
+            &mut cx.shared.system_config,
+            &mut cx.shared.activation_log,
+            &mut cx.shared.request_buffer,
+            &mut cx.shared.deadline_timer,
+            cx.local.console_event_signaler,
+        )
+        .await;
     }
 }
@@ -0,0 +1,55 @@
+// Mode change protocol. A change is requested by command (the console), by
+// the deadline miss policies or by SCHEDULE, and carried out by the regular
+// producer, which activates the sporadic tasks: from the request on it
+// activates no more sporadic work, and it switches mode at its first release
+// at which every job it activated before is over. Its job at that release is
+// armed with the deadline and period of the new mode, the sporadic tasks pick
+// the new mode up at their next release.
+use rtks_core::mode::{self, Activations, SystemMode};
+
+pub static ON_CALL_PRODUCER_ACTIVATIONS: Activations = Activations::new();
+pub static ACTIVATION_LOG_READER_ACTIVATIONS: Activations = Activations::new();
+
+// Called by the regular producer at each release, returns the mode of the job
+pub fn at_release() -> SystemMode {
+    let previous = mode::current();
+    if let Some(request) = mode::pending() {
+        let in_flight =
+            ON_CALL_PRODUCER_ACTIVATIONS.pending() || ACTIVATION_LOG_READER_ACTIVATIONS.pending();
+        if !in_flight && mode::complete(request) {
+            defmt::warn!(
+                "Mode change: {} -> {} ({})",
+                previous.name(),
+                request.mode.name(),
+                request.trigger
+            );
+        }
+    }
+    mode::current()
+}
+
+#[cfg(feature = "mode-schedule")]
+mod schedule {
+    use rtic_monotonics::{Monotonic, fugit::ExtU32};
+    use rtks_core::mode::{self, ModeChangeTrigger, SystemMode};
+
+    use crate::{activation_manager, time::Mono};
+
+    // Mode changes requested at fixed times from the start epoch (milliseconds)
+    const SCHEDULE: [(u32, SystemMode); 3] = [
+        (3_000, SystemMode::HighLoad),
+        (6_000, SystemMode::Degraded),
+        (8_000, SystemMode::Nominal),
+    ];
+
+    pub async fn mode_scheduler() {
+        for (at, mode) in SCHEDULE {
+            Mono::delay_until(activation_manager::epoch() + at.millis()).await;
+            defmt::info!("Mode change to {} requested by the schedule.", mode.name());
+            mode::request(mode, ModeChangeTrigger::Schedule);
+        }
+    }
+}
+
+#[cfg(feature = "mode-schedule")]
+pub use schedule::mode_scheduler;
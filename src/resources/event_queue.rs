@@ -5,7 +5,8 @@ use core::{
 
 use rtic_sync::signal::{Signal, SignalReader, SignalWriter};
 
-pub type EventType = ();
+// Cycle counter value at which the event was raised
+pub type EventType = u32;
 pub struct EventQueue;
 
 static mut EVENT_QUEUE: MaybeUninit<Signal<EventType>> = MaybeUninit::uninit();
@@ -38,8 +39,8 @@ pub struct EventQueueWaiter<'a> {
 }
 
 impl<'a> EventQueueWaiter<'a> {
-    pub async fn wait(&mut self) {
-        self.inner.wait().await;
+    pub async fn wait(&mut self) -> EventType {
+        self.inner.wait().await
     }
 }
 
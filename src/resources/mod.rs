@@ -1,4 +1,5 @@
-pub mod activation_log;
 pub mod event_queue;
 pub mod request_buffer;
 pub mod task_semaphore;
+
+pub use rtks_core::activation_log;
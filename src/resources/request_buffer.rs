@@ -1,63 +1,25 @@
-use heapless::Vec;
-use core::ops::AddAssign;
-use rtic_sync::signal::{SignalWriter};
+use rtic_sync::signal::SignalWriter;
+use rtks_core::Notify;
 
-const REQUEST_BUFFER_RANGE: usize = 5;
+pub use rtks_core::request_buffer::Request;
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-struct RequestBufferIndex(usize);
-
-impl RequestBufferIndex {
-    fn first() -> Self {
-        RequestBufferIndex(0)
-    }
-
-    fn last() -> Self {
-        RequestBufferIndex(REQUEST_BUFFER_RANGE - 1)
-    }
+// Barrier on which the on call producer waits for a new request. The signal
+// keeps a single wake-up for any number of deposits, the requests themselves
+// stay in the buffer until extracted
+pub struct Barrier {
+    inner: SignalWriter<'static, ()>,
 }
 
-// Override += operator
-impl AddAssign<usize> for RequestBufferIndex {
-    fn add_assign(&mut self, rhs: usize) {
-        self.0 = (self.0 + rhs) % REQUEST_BUFFER_RANGE;
+impl Barrier {
+    pub fn new(inner: SignalWriter<'static, ()>) -> Self {
+        Barrier { inner }
     }
 }
 
-pub struct RequestBuffer {
-    my_request_buffer: Vec<u32, REQUEST_BUFFER_RANGE>,
-    insert_index: RequestBufferIndex,
-    extract_index: RequestBufferIndex,
-    current_size: usize,
-    barrier_writer: SignalWriter<'static, ()>,
-}
-
-impl RequestBuffer {
-    pub fn new(barrier_writer: SignalWriter<'static, ()>) -> Self {
-        RequestBuffer {
-            my_request_buffer: Vec::new(),
-            insert_index: RequestBufferIndex::first(),
-            extract_index: RequestBufferIndex::first(),
-            current_size: 0,
-            barrier_writer: barrier_writer,
-        }
-    }
-
-    pub fn deposit(&mut self, activation_parameter: u32) -> bool {
-        if self.current_size < RequestBufferIndex::last().0 {
-            let _ = self.my_request_buffer.push(activation_parameter); // TODO: Handle possible error
-            self.insert_index += 1;
-            self.current_size += 1;
-            self.barrier_writer.write(());
-            return true;
-        } else {
-            return false;
-        }
+impl Notify for Barrier {
+    fn notify(&mut self) {
+        self.inner.write(());
     }
+}
 
-    pub fn extract(&mut self) -> u32 {
-        self.extract_index += 1;
-        self.current_size -= 1;
-        return self.my_request_buffer.pop().unwrap();
-    }
-}
\ No newline at end of file
+pub type RequestBuffer = rtks_core::request_buffer::RequestBuffer<Barrier>;
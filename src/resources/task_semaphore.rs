@@ -1,6 +1,18 @@
 use rtic_sync::signal::{Signal, SignalReader, SignalWriter};
+
+use crate::cycle_counter;
+
+// The semaphore carries the cycle counter value at which it was signalled,
+// i.e. the release time of the waiting task, and the sequence number of the
+// activation. Signals not yet waited for are absorbed by the latest one
+#[derive(Clone, Copy)]
+pub struct Activation {
+    pub arrival: u32,
+    pub sequence: u32,
+}
+
 pub struct TaskSemaphore {
-    inner: Signal<()>,
+    inner: Signal<Activation>,
 }
 
 impl TaskSemaphore {
@@ -20,21 +32,24 @@ impl TaskSemaphore {
 }
 
 pub struct TaskSemaphoreWaiter<'a> {
-    inner: SignalReader<'a, ()>,
+    inner: SignalReader<'a, Activation>,
 }
 
 impl<'a> TaskSemaphoreWaiter<'a> {
-    pub async fn wait(&mut self) {
-        self.inner.wait().await;
+    pub async fn wait(&mut self) -> Activation {
+        self.inner.wait().await
     }
 }
 
 pub struct TaskSemaphoreSignaler<'a> {
-    inner: SignalWriter<'a, ()>,
+    inner: SignalWriter<'a, Activation>,
 }
 
 impl<'a> TaskSemaphoreSignaler<'a> {
-    pub fn signal(&mut self) {
-        self.inner.write(());
+    pub fn signal(&mut self, sequence: u32) {
+        self.inner.write(Activation {
+            arrival: cycle_counter::now(),
+            sequence,
+        });
     }
 }
@@ -0,0 +1,81 @@
+// Stack painting, see rtks_core::stack. The stack is painted at the start of
+// init, sampled by the tasks at points deep in their jobs and scanned for the
+// high-water mark by the stack monitor and at the end of a bounded run.
+use core::ptr;
+
+use cortex_m::register::msp;
+use rtic_monotonics::{Monotonic, fugit::ExtU32};
+use rtks_core::stack::{self, LevelDepths, PAINT, StackUsage};
+
+use crate::time::Mono;
+
+// Start of RAM in memory.x
+const RAM_START: usize = 0x2000_0000;
+// Left unpainted below the stack pointer of `paint`, for its own frame
+const PAINT_MARGIN: usize = 64;
+// Period of the stack monitor reports (milliseconds)
+pub const REPORT_PERIOD: u32 = 5_000;
+
+unsafe extern "C" {
+    static _stack_start: u32;
+    static __sdata: u32;
+    // End of the statics
+    static __sheap: u32;
+}
+
+static LEVELS: LevelDepths = LevelDepths::new();
+
+// Lowest and highest address of the stack. flip-link moves the stack below
+// the statics, at the start of RAM, otherwise it grows down from the end of
+// RAM towards the statics
+fn bounds() -> (usize, usize) {
+    let top = &raw const _stack_start as usize;
+    let bottom = if top <= &raw const __sdata as usize {
+        RAM_START
+    } else {
+        &raw const __sheap as usize
+    };
+    (bottom, top)
+}
+
+// Called first thing in init, with the interrupts disabled: everything below
+// the current frame is free
+#[inline(never)]
+pub fn paint() {
+    let (bottom, _) = bounds();
+    let end = msp::read() as usize - PAINT_MARGIN;
+    for address in (bottom..end).step_by(4) {
+        unsafe { ptr::write_volatile(address as *mut u32, PAINT) };
+    }
+}
+
+// Records the depth of the stack at the caller, a task running at `priority`
+#[inline(always)]
+pub fn sample(priority: u8) {
+    let (_, top) = bounds();
+    LEVELS.record(priority, (top - msp::read() as usize) as u32);
+}
+
+pub fn usage() -> StackUsage {
+    let (bottom, top) = bounds();
+    let size = (top - bottom) as u32;
+    let words = (bottom..top)
+        .step_by(4)
+        .map(|address| unsafe { ptr::read_volatile(address as *const u32) });
+    StackUsage {
+        size,
+        high_water_mark: size - stack::painted(words) * 4,
+        levels: LEVELS.deepest(),
+    }
+}
+
+// STACK_MONITOR
+// Runs in the background, the scan takes a fraction of a millisecond
+pub async fn stack_monitor() -> ! {
+    let mut next_time = Mono::now();
+    loop {
+        next_time += REPORT_PERIOD.millis();
+        Mono::delay_until(next_time).await;
+        usage().log_high_water_mark();
+    }
+}
@@ -1,51 +1,118 @@
 use crate::{
     activation_manager,
+    cycle_counter,
+    fault_injection,
+    mode_change::ACTIVATION_LOG_READER_ACTIVATIONS,
     resources::{
-        activation_log::ActivationLog,
+        activation_log::{ActivationLog, HISTORY_LENGTH},
         task_semaphore::TaskSemaphoreWaiter,
     },
-    production_workload,
-    deadline::DeadlineProtectedObject,
-    time::{Mono, Instant},
+    deadline::{DeadlineHandle, DeadlineTimer},
+    stack,
+    time::Mono,
+};
+use rtic_monotonics::{Monotonic, fugit::ExtU32};
+use rtks_core::{
+    budget::{ExecutionBudget, OverrunAction},
+    config::SystemConfig,
+    mode,
+    production_workload::{self, WorkloadOutcome},
+    task_set::ACTIVATION_LOG_READER,
+    timing::JobTimestamps,
 };
-use rtic_sync::signal::SignalWriter;
-use rtic_monotonics::Monotonic;
 
-pub const DEADLINE: u32 = 1_000;
+pub const DEADLINE: u32 = ACTIVATION_LOG_READER.deadline;
 
 pub async fn activation_log_reader(
     semaphore: &mut TaskSemaphoreWaiter<'_>,
+    system_config: &mut impl rtic::Mutex<T = SystemConfig>,
     activation_log: &mut impl rtic::Mutex<T = ActivationLog>,
-    activation_writer: &mut SignalWriter<'static, Instant>,
-    deadline_protected_object: &mut impl rtic::Mutex<T = DeadlineProtectedObject>,
-    activation_count: &mut u32,
+    deadline_timer: &mut impl rtic::Mutex<T = DeadlineTimer>,
+    deadline: DeadlineHandle,
+    execution_budget: &ExecutionBudget,
 ) -> ! {
-    activation_manager::activation_sporadic().await;
+    activation_manager::activation_sporadic(&ACTIVATION_LOG_READER).await;
+    // Sequence number of the last log entry read
+    let mut last_read = 0;
     loop {
-        semaphore.wait().await;
+        let activation = semaphore.wait().await;
+        let Some((release_time, release)) =
+            activation_manager::sporadic_release(deadline_timer, deadline, activation.arrival)
+                .await
+        else {
+            ACTIVATION_LOG_READER_ACTIVATIONS.served(activation.sequence);
+            continue;
+        };
+
+        // Workload of the job in the current mode, a change applies from the
+        // next release
+        let current_mode = mode::current();
+        let workload = system_config
+            .lock(|config| config.for_mode(current_mode).activation_log_reader_workload);
+        // Release the job and arm its deadline, relative to the release so
+        // that the queueing delay counts against it
+        let job =
+            deadline_timer.lock(|timer| timer.arm(deadline, release_time + DEADLINE.millis()));
+        fault_injection::delay_release(&ACTIVATION_LOG_READER, job.id()).await;
+        let start = cycle_counter::now();
+        let mut execution =
+            execution_budget.start_scaled(job.id(), start, current_mode.parameters().load_factor);
 
-        // Signal activation to the deadline watchdog
-        activation_writer.write(Mono::now());
-        *activation_count += 1;
+        if !deadline_timer.lock(|timer| timer.object(deadline).begin_job()) {
+            deadline_timer.lock(|timer| timer.skip(job));
+            execution.finish(cycle_counter::now());
+            ACTIVATION_LOG_READER_ACTIVATIONS.served(activation.sequence);
+            continue;
+        }
 
-        if let Err(err) = production_workload::small_whetstone(1_000) {
-            defmt::error!(
+        let workload = workload
+            .saturating_mul(fault_injection::workload_factor(&ACTIVATION_LOG_READER, job.id()));
+        match production_workload::execute_abortable_for(workload, || {
+            stack::sample(ACTIVATION_LOG_READER.priority);
+            execution.check(cycle_counter::now()) == OverrunAction::AbortJob
+                || deadline_timer.lock(|timer| timer.object(deadline).abort_requested(job.id()))
+        }) {
+            Ok(WorkloadOutcome::Completed) => {}
+            Ok(WorkloadOutcome::Aborted) => defmt::warn!("Activation log reader job aborted."),
+            Err(err) => defmt::error!(
                 "Error computing whetstone in activation log reader: {}",
                 err
-            );
+            ),
         }
-        activation_log.lock(|al| {
-            let (activations, last) = al.read();
-            defmt::info!(
-                "Activation log reader: activations = {}, last = {}",
-                activations,
-                last
-            );
+        // Copy the entries logged since the previous job, and log them outside of the lock
+        let mut entries = [None; HISTORY_LENGTH];
+        let ((activations, last), dropped, interarrival) = activation_log.lock(|al| {
+            for (slot, entry) in entries.iter_mut().zip(al.entries_since(last_read)) {
+                *slot = Some(entry);
+            }
+            let dropped = al.dropped_since(last_read);
+            last_read = al.last_sequence();
+            (al.read(), dropped, al.interarrival())
         });
+        defmt::info!(
+            "Activation log reader: activations = {}, last = {}",
+            activations,
+            last
+        );
+        if dropped > 0 {
+            defmt::warn!("Activation log reader: {} entries overwritten before being read", dropped);
+        }
+        for entry in entries.iter().flatten() {
+            defmt::info!("Activation log reader: {}", entry);
+        }
+        if let Some((min, max)) = interarrival {
+            defmt::info!("Activation log reader: interarrival min = {}, max = {}", min, max);
+        }
 
         // Cancel deadline
-        deadline_protected_object.lock( |dpo| {
-            dpo.cancel_deadline(*activation_count);
+        let completion = cycle_counter::now();
+        execution.finish(completion);
+        deadline_timer.lock(|timer| {
+            if !fault_injection::cancellation_suppressed(&ACTIVATION_LOG_READER, job.id()) {
+                timer.cancel(job, Mono::now());
+            }
+            timer.object(deadline).job_completed(JobTimestamps { release, start, completion });
         });
+        ACTIVATION_LOG_READER_ACTIVATIONS.served(activation.sequence);
     }
 }
@@ -1,36 +1,61 @@
 use crate::{
     activation_manager,
+    cycle_counter,
+    fault_injection,
     resources::{activation_log::ActivationLog, event_queue::EventQueueWaiter},
-    time::{Mono, Instant},
-    deadline::DeadlineProtectedObject,
+    stack,
+    time::Mono,
+    deadline::{DeadlineHandle, DeadlineTimer},
 };
-use rtic_sync::signal::SignalWriter;
-use rtic_monotonics::Monotonic;
+use rtic_monotonics::{Monotonic, fugit::ExtU32};
+use rtks_core::{budget::ExecutionBudget, task_set::EXTERNAL_EVENT_SERVER, timing::JobTimestamps};
 
-pub const DEADLINE: u32 = 100;
+pub const DEADLINE: u32 = EXTERNAL_EVENT_SERVER.deadline;
 
 pub async fn external_event_server(
     events: &mut EventQueueWaiter<'_>,
     activation_log: &mut impl rtic::Mutex<T = ActivationLog>,
-    activation_writer: &mut SignalWriter<'static, Instant>,
-    deadline_protected_object: &mut impl rtic::Mutex<T = DeadlineProtectedObject>,
-    activation_count: &mut u32,
+    deadline_timer: &mut impl rtic::Mutex<T = DeadlineTimer>,
+    deadline: DeadlineHandle,
+    execution_budget: &ExecutionBudget,
 ) -> ! {
-    activation_manager::activation_sporadic().await;
+    activation_manager::activation_sporadic(&EXTERNAL_EVENT_SERVER).await;
     loop {
-        events.wait().await;
+        let arrival = events.wait().await;
+        let Some((release_time, release)) =
+            activation_manager::sporadic_release(deadline_timer, deadline, arrival).await
+        else {
+            continue;
+        };
 
-        // Signal activation to the deadline watchdog
-        activation_writer.write(Mono::now());
-        *activation_count += 1;
+        // Release the job and arm its deadline, relative to the release so
+        // that the queueing delay counts against it
+        let job =
+            deadline_timer.lock(|timer| timer.arm(deadline, release_time + DEADLINE.millis()));
+        fault_injection::delay_release(&EXTERNAL_EVENT_SERVER, job.id()).await;
+        let start = cycle_counter::now();
+        let execution = execution_budget.start(job.id(), start);
 
+        if !deadline_timer.lock(|timer| timer.object(deadline).begin_job()) {
+            deadline_timer.lock(|timer| timer.skip(job));
+            execution.finish(cycle_counter::now());
+            continue;
+        }
+
+        // Logged at the time the interrupt was raised
         activation_log.lock(|al| {
-            al.write();
+            stack::sample(EXTERNAL_EVENT_SERVER.priority);
+            al.write(release_time);
         });
 
         // Cancel deadline
-        deadline_protected_object.lock( |dpo| {
-            dpo.cancel_deadline(*activation_count);
+        let completion = cycle_counter::now();
+        execution.finish(completion);
+        deadline_timer.lock(|timer| {
+            if !fault_injection::cancellation_suppressed(&EXTERNAL_EVENT_SERVER, job.id()) {
+                timer.cancel(job, Mono::now());
+            }
+            timer.object(deadline).job_completed(JobTimestamps { release, start, completion });
         });
     }
 }
@@ -0,0 +1,48 @@
+use crate::{cycle_counter, fault_injection, resources::event_queue::EventQueueSignaler, stack};
+use rtic_monotonics::fugit::ExtU32;
+use rtks_core::task_set::{EXTERNAL_EVENT_SERVER, EXTERNAL_INTERRUPT_PRIORITY};
+use stm32f4xx_hal::{
+    pac::TIM5,
+    timer::{CounterUs, Flag},
+};
+
+// Sporadic rate of the external interrupt source (milliseconds)
+pub const MIN_INTERARRIVAL: u32 = EXTERNAL_EVENT_SERVER.period();
+pub const MAX_JITTER: u32 = 2_000;
+
+// Seed of the pseudo-random jitter sequence
+pub const JITTER_SEED: u32 = 0x2545_F491;
+
+// Time until the first interrupt, computed before the timer is started in init
+pub fn first_interarrival(seed: &mut u32) -> u32 {
+    MIN_INTERARRIVAL + jitter(seed)
+}
+
+// Bound to the TIM5 update interrupt, plays the role of the external device
+// raising the interrupt served by the External_Event_Server
+pub fn external_interrupt(
+    timer: &mut CounterUs<TIM5>,
+    event_signaler: &mut EventQueueSignaler<'_>,
+    seed: &mut u32,
+) {
+    timer.clear_flags(Flag::Update);
+
+    event_signaler.signal(cycle_counter::now());
+    stack::sample(EXTERNAL_INTERRUPT_PRIORITY);
+    defmt::debug!("External interrupt raised.");
+
+    // Re-arm the timer for the next sporadic occurrence
+    let next_interarrival = fault_injection::event_interarrival(&EXTERNAL_EVENT_SERVER)
+        .unwrap_or_else(|| MIN_INTERARRIVAL + jitter(seed));
+    if let Err(err) = timer.start(next_interarrival.millis()) {
+        defmt::error!("Error re-arming external interrupt timer: {}", err);
+    }
+}
+
+// Xorshift32 step, good enough to spread the interarrivals over [0, MAX_JITTER]
+fn jitter(seed: &mut u32) -> u32 {
+    *seed ^= *seed << 13;
+    *seed ^= *seed >> 17;
+    *seed ^= *seed << 5;
+    *seed % (MAX_JITTER + 1)
+}
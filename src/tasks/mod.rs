@@ -1,4 +1,5 @@
 pub mod external_event_server;
+pub mod external_interrupt;
 pub mod activation_log_reader;
 pub mod on_call_producer_task;
 pub mod regular_producer_task;
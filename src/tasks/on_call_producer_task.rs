@@ -1,49 +1,102 @@
 use crate::{
-    production_workload, 
     activation_manager,
-    deadline::DeadlineProtectedObject,
-    time::{Mono, Instant}};
-use rtic_sync::signal::{SignalReader, SignalWriter};
+    cycle_counter,
+    fault_injection,
+    mode_change::ON_CALL_PRODUCER_ACTIVATIONS,
+    deadline::{DeadlineHandle, DeadlineTimer, JobId},
+    stack,
+    time::Mono};
+use rtic_sync::signal::SignalReader;
 use rtic::Mutex;  
-use rtic_monotonics::Monotonic;
+use rtic_monotonics::{Monotonic, fugit::ExtU32};
+use rtks_core::{
+    budget::{BudgetedJob, ExecutionBudget, OverrunAction},
+    mode,
+    production_workload::{self, WorkloadOutcome},
+    task_set::ON_CALL_PRODUCER,
+    timing::JobTimestamps,
+};
 
 
-pub const DEADLINE: u32 = 800;
+pub const DEADLINE: u32 = ON_CALL_PRODUCER.deadline;
 
 pub async fn on_call_producer_task(
     request_buffer: &mut impl Mutex<T = crate::resources::request_buffer::RequestBuffer>,
     current_workload: &mut u32,
     barrier_reader: &mut SignalReader<'static, ()>,
-    activation_writer: &mut SignalWriter<'static, Instant>,
-    deadline_protected_object: &mut impl rtic::Mutex<T = DeadlineProtectedObject>,
-    activation_count: &mut u32
+    deadline_timer: &mut impl Mutex<T = DeadlineTimer>,
+    deadline: DeadlineHandle,
+    execution_budget: &ExecutionBudget,
 ) -> ! {
-    activation_manager::activation_sporadic().await;
+    activation_manager::activation_sporadic(&ON_CALL_PRODUCER).await;
     loop {
-        barrier_reader.wait().await;
+        // Entry barrier `current_size > 0`: wait only when the buffer is empty, a
+        // request deposited meanwhile leaves a pending wake-up behind
+        let request = loop {
+            if let Some(request) = request_buffer.lock(|buffer| buffer.extract()) {
+                break request;
+            }
+            barrier_reader.wait().await;
+        };
+        let Some((release_time, release)) =
+            activation_manager::sporadic_release(deadline_timer, deadline, request.arrival).await
+        else {
+            ON_CALL_PRODUCER_ACTIVATIONS.served_next();
+            continue;
+        };
 
-        // Signal activation to the deadline watchdog
-        activation_writer.write(Mono::now());
-        *activation_count += 1;
+        // Release the job and arm its deadline, relative to the release so
+        // that the queueing delay counts against it
+        let job =
+            deadline_timer.lock(|timer| timer.arm(deadline, release_time + DEADLINE.millis()));
+        fault_injection::delay_release(&ON_CALL_PRODUCER, job.id()).await;
+        let start = cycle_counter::now();
+        // Budget scaled like the workload deposited with the request
+        let load_factor = mode::current().parameters().load_factor;
+        let mut execution = execution_budget.start_scaled(job.id(), start, load_factor);
 
-        request_buffer.lock( |buffer| {
-            *current_workload = buffer.extract();
-        });
-        on_call_producer_operation(*current_workload);
+        if !deadline_timer.lock(|timer| timer.object(deadline).begin_job()) {
+            // The request of the skipped release is dropped
+            deadline_timer.lock(|timer| timer.skip(job));
+            execution.finish(cycle_counter::now());
+            ON_CALL_PRODUCER_ACTIVATIONS.served_next();
+            continue;
+        }
+
+        *current_workload = request.activation_parameter
+            .saturating_mul(fault_injection::workload_factor(&ON_CALL_PRODUCER, job.id()));
+        on_call_producer_operation(*current_workload, deadline_timer, job, &mut execution);
 
         // Cancel deadline
-        deadline_protected_object.lock( |dpo| {
-            dpo.cancel_deadline(*activation_count);
+        let completion = cycle_counter::now();
+        execution.finish(completion);
+        deadline_timer.lock(|timer| {
+            if !fault_injection::cancellation_suppressed(&ON_CALL_PRODUCER, job.id()) {
+                timer.cancel(job, Mono::now());
+            }
+            timer.object(deadline).job_completed(JobTimestamps { release, start, completion });
         });
+        ON_CALL_PRODUCER_ACTIVATIONS.served_next();
     }
 } 
 
-fn on_call_producer_operation(load: u32) {
-    if let Err(err) = production_workload::small_whetstone(load) {
-        defmt::error!(
+// `load` is the execution budget deposited by the regular producer (microseconds)
+fn on_call_producer_operation(
+    load: u32,
+    deadline_timer: &mut impl Mutex<T = DeadlineTimer>,
+    job: JobId,
+    execution: &mut BudgetedJob<'_>,
+) {
+    match production_workload::execute_abortable_for(load, || {
+        stack::sample(ON_CALL_PRODUCER.priority);
+        execution.check(cycle_counter::now()) == OverrunAction::AbortJob
+            || deadline_timer.lock(|timer| timer.object(job.handle()).abort_requested(job.id()))
+    }) {
+        Ok(WorkloadOutcome::Completed) => defmt::info!("End of sporadic activation."),
+        Ok(WorkloadOutcome::Aborted) => defmt::warn!("On call producer job aborted."),
+        Err(err) => defmt::error!(
                 "Error computing whetstone in on call producer operation: {}",
                 err
-            );
+            ),
     }
-    defmt::info!("End of sporadic activation.");
-}
\ No newline at end of file
+}
@@ -1,56 +1,111 @@
 use crate::{
-    auxiliary,
     activation_manager,
-    deadline::DeadlineProtectedObject,
-    production_workload,
-    resources::{request_buffer::RequestBuffer, task_semaphore::TaskSemaphoreSignaler},
+    cycle_counter,
+    fault_injection,
+    mode_change::{self, ACTIVATION_LOG_READER_ACTIVATIONS, ON_CALL_PRODUCER_ACTIVATIONS},
+    deadline::{DeadlineHandle, DeadlineTimer},
+    resources::{
+        request_buffer::{Request, RequestBuffer},
+        task_semaphore::TaskSemaphoreSignaler,
+    },
+    stack,
     time::{Mono, Instant},
 };
 use rtic_monotonics::{Monotonic, fugit::ExtU32};
-
-pub const PERIOD: u32 = 1_000;
-pub const DEADLINE: u32 = 500;
-
-const REGULAR_PRODUCER_WORKLOAD: u32 = 756;
-const ON_CALL_PRODUCER_WORKLOAD: u32 = 278;
-const ACTIVATION_CONDITION: usize = 2;
+use rtks_core::{
+    auxiliary,
+    budget::{ExecutionBudget, OverrunAction},
+    config::SystemConfig,
+    mode,
+    production_workload::{self, WorkloadOutcome},
+    task_set::REGULAR_PRODUCER,
+    timing::JobTimestamps,
+};
 
 pub async fn regular_producer_task(
     next_time: &mut Instant,
+    system_config: &mut impl rtic::Mutex<T = SystemConfig>,
     request_buffer: &mut impl rtic::Mutex<T = RequestBuffer>,
     activation_log_reader_signaler: &mut TaskSemaphoreSignaler<'_>,
-    deadline_protected_object: &mut impl rtic::Mutex<T = DeadlineProtectedObject>,
-    activation_count: &mut u32,
+    deadline_timer: &mut impl rtic::Mutex<T = DeadlineTimer>,
+    deadline: DeadlineHandle,
+    execution_budget: &ExecutionBudget,
 ) -> ! {
-    activation_manager::activation_cyclic().await;
+    *next_time = activation_manager::activation_cyclic(&REGULAR_PRODUCER).await;
     loop {
-        *next_time = Mono::now() + PERIOD.millis();
-        *activation_count += 1;
+        // Mode and configuration of the job, a change applies from the next release
+        let current_mode = mode_change::at_release();
+        let config = system_config.lock(|config| config.for_mode(current_mode));
+        // Nominal release of the job, releases are exactly one period apart so
+        // the latency of a wake-up does not delay the following ones
+        let release_time = *next_time;
+        *next_time += config.period.millis();
+        let release = cycle_counter::at(release_time);
+
+        // Release the job and arm its deadline, relative to the nominal release
+        let job = deadline_timer
+            .lock(|timer| timer.arm(deadline, release_time + config.deadline.millis()));
+        fault_injection::delay_release(&REGULAR_PRODUCER, job.id()).await;
+        let start = cycle_counter::now();
+        let mut execution =
+            execution_budget.start_scaled(job.id(), start, current_mode.parameters().load_factor);
+
+        if !deadline_timer.lock(|timer| timer.object(deadline).begin_job()) {
+            deadline_timer.lock(|timer| timer.skip(job));
+            execution.finish(cycle_counter::now());
+            Mono::delay_until(*next_time).await;
+            continue;
+        }
 
         // BEGIN REGULAR_PRODUCER_OPERATION
-        if let Err(err) = production_workload::small_whetstone(REGULAR_PRODUCER_WORKLOAD) {
-            defmt::error!(
+        let workload = config.regular_producer_workload
+            .saturating_mul(fault_injection::workload_factor(&REGULAR_PRODUCER, job.id()));
+        match production_workload::execute_abortable_for(workload, || {
+            stack::sample(REGULAR_PRODUCER.priority);
+            execution.check(cycle_counter::now()) == OverrunAction::AbortJob
+                || deadline_timer.lock(|timer| timer.object(deadline).abort_requested(job.id()))
+        }) {
+            Ok(WorkloadOutcome::Completed) => {}
+            Ok(WorkloadOutcome::Aborted) => defmt::warn!("Regular producer job aborted."),
+            Err(err) => defmt::error!(
                 "Error computing whetstone in regular producer operation: {}",
                 err
-            );
+            ),
         }
-        if auxiliary::due_activation(ACTIVATION_CONDITION) {
+        // No sporadic work is activated while a mode change waits for the jobs
+        // of the old mode, the on call producer is optional work shed in some modes
+        let activating = mode::pending().is_none();
+        if auxiliary::due_activation(config.activation_condition, &config.moduli)
+            && activating
+            && current_mode.parameters().on_call_producer
+        {
             // on_call_producer activation
             request_buffer.lock(|buffer| {
-                if !buffer.deposit(ON_CALL_PRODUCER_WORKLOAD) {
+                let request = Request {
+                    activation_parameter: config.on_call_producer_workload,
+                    arrival: cycle_counter::now(),
+                };
+                if buffer.deposit(request) {
+                    ON_CALL_PRODUCER_ACTIVATIONS.issue();
+                } else {
                     defmt::info!("Failed sporadic activation.");
                 }
             })
         }
-        if auxiliary::check_due() {
-            activation_log_reader_signaler.signal();
+        if auxiliary::check_due(&config.moduli) && activating {
+            activation_log_reader_signaler.signal(ACTIVATION_LOG_READER_ACTIVATIONS.issue());
         }
         defmt::info!("End of cyclic activation.");
         // END REGULAR_PRODUCER_OPERATION
 
         // Cancel deadline
-        deadline_protected_object.lock( |dpo| {
-            dpo.cancel_deadline(*activation_count);
+        let completion = cycle_counter::now();
+        execution.finish(completion);
+        deadline_timer.lock(|timer| {
+            if !fault_injection::cancellation_suppressed(&REGULAR_PRODUCER, job.id()) {
+                timer.cancel(job, Mono::now());
+            }
+            timer.object(deadline).job_completed(JobTimestamps { release, start, completion });
         });
 
         Mono::delay_until(*next_time).await;
@@ -1,9 +1,16 @@
 use rtic_monotonics::Monotonic;
 
 // Timer interrupt setup and timer type creation
-// stm32_tim2_monotonic!(Mono, 1_000);
-rtic_monotonics::systick_monotonic!(Mono, 1_000);
+#[cfg(not(feature = "tim2-monotonic"))]
+rtic_monotonics::systick_monotonic!(Mono, rtks_core::time::TICK_RATE_HZ);
+// Tickless 32 bit timer, extended to 64 bit by the overflow interrupt
+#[cfg(feature = "tim2-monotonic")]
+rtic_monotonics::stm32_tim2_monotonic!(Mono, rtks_core::time::TICK_RATE_HZ);
+
 // defmt timestamp
+#[cfg(not(feature = "tim2-monotonic"))]
 defmt::timestamp!("{=u32:ms}", Mono::now().duration_since_epoch().to_millis());
+#[cfg(feature = "tim2-monotonic")]
+defmt::timestamp!("{=u64:us}", Mono::now().duration_since_epoch().to_micros());
 
 pub type Instant = <Mono as rtic_monotonics::Monotonic>::Instant;
\ No newline at end of file